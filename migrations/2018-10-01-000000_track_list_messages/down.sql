@@ -0,0 +1 @@
+ALTER TABLE guilds DROP COLUMN list_message_ids;
@@ -0,0 +1,2 @@
+-- the messages holding the pages of the colour list, so they can all be replaced on a refresh.
+ALTER TABLE guilds ADD COLUMN list_message_ids NUMERIC(64)[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,3 @@
+-- unix timestamp for when a guild that removed the bot gets its records deleted.
+-- cleared when the bot is invited back before then.
+ALTER TABLE guilds ADD COLUMN purge_at BIGINT;
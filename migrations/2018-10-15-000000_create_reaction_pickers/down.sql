@@ -0,0 +1,2 @@
+DROP TABLE colour_reactions;
+DROP TABLE picker_messages;
@@ -0,0 +1,17 @@
+-- messages in the colour channel that hand out colours through reactions.
+CREATE TABLE picker_messages
+(
+    -- the discord snowflake of the message.
+    id NUMERIC(64) PRIMARY KEY,
+    guild_id NUMERIC(64) REFERENCES guilds ON DELETE CASCADE NOT NULL
+);
+
+-- which emoji on which picker message gives out each colour.
+CREATE TABLE colour_reactions
+(
+    colour_id NUMERIC(64) PRIMARY KEY REFERENCES colours ON DELETE CASCADE,
+    message_id NUMERIC(64) REFERENCES picker_messages ON DELETE CASCADE NOT NULL,
+    -- either a unicode emoji or a custom emoji in the `<:name:id>` format.
+    emoji TEXT NOT NULL,
+    UNIQUE (message_id, emoji)
+);
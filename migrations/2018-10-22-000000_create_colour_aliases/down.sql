@@ -0,0 +1 @@
+DROP TABLE colour_aliases;
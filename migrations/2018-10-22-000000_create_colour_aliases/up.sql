@@ -0,0 +1,7 @@
+-- other names a colour can be found by, alongside its own name.
+CREATE TABLE colour_aliases
+(
+    colour_id NUMERIC(64) REFERENCES colours ON DELETE CASCADE NOT NULL,
+    name TEXT NOT NULL,
+    PRIMARY KEY (colour_id, name)
+);
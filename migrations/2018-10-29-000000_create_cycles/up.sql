@@ -0,0 +1,21 @@
+-- a user or a role that rotates through a set of the guild's colours on a schedule.
+CREATE TABLE cycles
+(
+    id SERIAL PRIMARY KEY,
+    guild_id NUMERIC(64) REFERENCES guilds ON DELETE CASCADE NOT NULL,
+    -- exactly one of these is set, a user gets handed the colour roles, a role has its colour changed.
+    user_id NUMERIC(64),
+    role_id NUMERIC(64),
+    -- deleted colours are skipped when the cycle runs.
+    colour_ids NUMERIC(64)[] NOT NULL,
+    interval_seconds BIGINT NOT NULL,
+    -- index into colour_ids of the colour applied on the next run.
+    position INTEGER NOT NULL DEFAULT 0,
+    -- unix timestamp of the next run.
+    next_run BIGINT NOT NULL,
+    CHECK ((user_id IS NULL) <> (role_id IS NULL)),
+    UNIQUE (guild_id, user_id),
+    UNIQUE (role_id)
+);
+
+CREATE INDEX cycles_next_run_idx ON cycles (next_run);
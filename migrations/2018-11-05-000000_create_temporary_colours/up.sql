@@ -0,0 +1,14 @@
+-- colours handed out for a limited time, taken off again once they expire.
+CREATE TABLE temporary_colours
+(
+    guild_id NUMERIC(64) REFERENCES guilds ON DELETE CASCADE NOT NULL,
+    user_id NUMERIC(64) NOT NULL,
+    colour_id NUMERIC(64) REFERENCES colours ON DELETE CASCADE NOT NULL,
+    -- the colour roles the member had before, given back on expiry.
+    previous_colour_ids NUMERIC(64)[] NOT NULL,
+    -- unix timestamp of when the colour comes off.
+    expires_at BIGINT NOT NULL,
+    PRIMARY KEY (guild_id, user_id)
+);
+
+CREATE INDEX temporary_colours_expires_at_idx ON temporary_colours (expires_at);
@@ -0,0 +1,9 @@
+-- what a member needs before they can pick a colour, colours without a row are open to everyone.
+CREATE TABLE colour_requirements
+(
+    colour_id NUMERIC(64) PRIMARY KEY REFERENCES colours ON DELETE CASCADE,
+    -- having any one of these roles is enough, an empty list doesn't require a role.
+    role_ids NUMERIC(64)[] NOT NULL DEFAULT '{}',
+    boosters_only BOOLEAN NOT NULL DEFAULT FALSE,
+    min_days INTEGER NOT NULL DEFAULT 0
+);
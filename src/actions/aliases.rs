@@ -0,0 +1,104 @@
+use failure::Error;
+
+use diesel;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+
+use db::models::{Colour, ColourAlias, Guild};
+
+use db::schema::colour_aliases::dsl as a;
+use db::schema::colour_aliases::table as aliases_table;
+use db::schema::colours::dsl as c;
+use db::schema::colours::table as colours_table;
+
+/// Limits so the alias list for a colour stays short enough to show in one message.
+pub const MAX_ALIASES: usize = 10;
+pub const MAX_ALIAS_LENGTH: usize = 32;
+
+/// Gets the aliases for a single colour, in alphabetical order.
+pub fn find_for_colour(colour: &Colour, connection: &PgConnection) -> Vec<String> {
+    ColourAlias::belonging_to(colour)
+        .select(a::name)
+        .order(a::name)
+        .load::<String>(connection)
+        .unwrap_or_default()
+}
+
+/// Gets the aliases for every colour in the guild.
+pub fn find_for_guild(guild: &Guild, connection: &PgConnection) -> Vec<ColourAlias> {
+    aliases_table
+        .inner_join(colours_table)
+        .filter(c::guild_id.eq(&guild.id))
+        .select((a::colour_id, a::name))
+        .load::<ColourAlias>(connection)
+        .unwrap_or_default()
+}
+
+/// Adds another name for the colour.
+/// Fails if any colour in the guild already goes by that name, since the lookup couldn't tell them apart.
+pub fn add_alias(
+    colour: &Colour,
+    alias: &str,
+    guild: &Guild,
+    connection: &PgConnection,
+) -> Result<ColourAlias, Error> {
+    let alias = alias.trim();
+
+    if alias.is_empty() || alias.chars().count() > MAX_ALIAS_LENGTH {
+        return Err(format_err!(
+            "Aliases need to be between 1 and {} characters long.",
+            MAX_ALIAS_LENGTH
+        ));
+    }
+
+    if find_for_colour(colour, connection).len() >= MAX_ALIASES {
+        return Err(format_err!(
+            "{} already has {} aliases, remove one first.",
+            colour.name,
+            MAX_ALIASES
+        ));
+    }
+
+    let lowercase = alias.to_lowercase();
+    let colour_names = Colour::belonging_to(guild)
+        .select(c::name)
+        .load::<String>(connection)?;
+    let alias_names = find_for_guild(guild, connection)
+        .into_iter()
+        .map(|existing| existing.name);
+
+    if colour_names
+        .into_iter()
+        .chain(alias_names)
+        .any(|name| name.to_lowercase() == lowercase)
+    {
+        return Err(format_err!(
+            "A colour already goes by the name \"{}\" in this server.",
+            alias
+        ));
+    }
+
+    Ok(diesel::insert_into(aliases_table)
+        .values(&ColourAlias {
+            colour_id: colour.id.clone(),
+            name: alias.to_string(),
+        }).get_result::<ColourAlias>(connection)?)
+}
+
+/// Removes an alias from the colour, ignoring case. Returns the alias that was removed.
+pub fn remove_alias(
+    colour: &Colour,
+    alias: &str,
+    connection: &PgConnection,
+) -> Result<String, Error> {
+    let lowercase = alias.trim().to_lowercase();
+
+    let existing = find_for_colour(colour, connection)
+        .into_iter()
+        .find(|name| name.to_lowercase() == lowercase)
+        .ok_or_else(|| format_err!("{} has no alias called \"{}\".", colour.name, alias.trim()))?;
+
+    diesel::delete(aliases_table.find((&colour.id, &existing))).execute(connection)?;
+
+    Ok(existing)
+}
@@ -7,7 +7,7 @@ pub const HELP_MESSAGE_FOR_CHANNEL: &str = "
 
 **To clear your colour:**
 
-1) type \"!c clear\" (don't include the quotes.)
+1) type \"{prefix} clear\" (don't include the quotes.)
 ";
 
 fn make_usage_example(name: &str) -> String {
@@ -20,7 +20,8 @@ fn make_usage_example(name: &str) -> String {
     )
 }
 
-pub fn generate_help_message(names: &[String]) -> String {
+/// Builds the help message posted with the colour list, `prefix` is shown in the command examples.
+pub fn generate_help_message(names: &[String], prefix: &str) -> String {
     let usage_examples = names
         .iter()
         .take(2)
@@ -46,6 +47,7 @@ pub fn generate_help_message(names: &[String]) -> String {
 
 *Colours avaliable:*
 ",
-        HELP_MESSAGE_FOR_CHANNEL, usage_examples
+        HELP_MESSAGE_FOR_CHANNEL.replace("{prefix}", prefix),
+        usage_examples
     )
 }
@@ -1,20 +1,20 @@
+use actions;
 use actions::guilds;
 
-use constants::commands::MAX_STRING_COMPARE_DELTA;
-
-use edit_distance::edit_distance;
-
-use std::usize;
+use std::iter;
+use std::path::PathBuf;
 
 use diesel;
 use diesel::pg::PgConnection;
 use diesel::prelude::*;
 use diesel::BelongingToDsl;
 
+use colours::matching::match_name;
 use colours::models::ParsedColour;
 
-use db::models::Colour;
+use db::models::{Colour, ColourAlias};
 use db::models::Guild;
+use db::models::GuildSettings;
 
 use db::schema::colours as colours_schema;
 use db::schema::colours::dsl as c;
@@ -22,10 +22,10 @@ use db::schema::colours::table as colours_table;
 
 use serenity::framework::standard::CommandError;
 use serenity::model::guild::{Guild as DiscordGuild, Member as DiscordMember, Role as DiscordRole};
-use serenity::model::id::{GuildId, RoleId};
+use serenity::model::id::{GuildId, RoleId, UserId};
+use serenity::model::permissions::Permissions;
 use serenity::model::user::User as DiscordUser;
 use serenity::prelude::ModelError;
-use serenity::utils::Colour as DiscordColour;
 use serenity::Error as SerenityError;
 
 use bigdecimal::BigDecimal;
@@ -33,40 +33,102 @@ use num_traits::cast::{FromPrimitive, ToPrimitive};
 use parking_lot::RwLockWriteGuard;
 
 use colours::images::ColourListBuilder;
-use colours::images::Name;
+use colours::images::{ColourListImage, ListColour, Name, OutputFormat};
+use CONFIG;
+use ROLE_EDITS;
 
-/// Searches the db for a colour from a name param for a guild.
+/// Searches the db for a colour from a name param for a guild, aliases included.
 pub fn find_from_name(name: &str, guild: &Guild, connection: &PgConnection) -> Option<Colour> {
-    // Colour::belonging_to(guild)
-    //     .filter(c::name.ilike(format!("%{}%", name)))
-    //     .get_result::<Colour>(connection)
-    //     .ok()
+    find_matches_from_name(name, guild, connection)
+        .into_iter()
+        .next()
+}
+
+/// Same as `find_from_name`, but gives back every colour tied for the closest name.
+pub fn find_matches_from_name(name: &str, guild: &Guild, connection: &PgConnection) -> Vec<Colour> {
     let name = name.trim();
-    let list = find_all(guild, connection)?;
+    let aliases = actions::aliases::find_for_guild(guild, connection);
+
+    find_all(guild, connection)
+        .map(|list| {
+            get_closest_colours_with_aliases(
+                name,
+                &list,
+                &aliases,
+                guild.settings().fuzzy_tolerance,
+            )
+        }).unwrap_or_default()
+}
 
-    get_nearest_colour_for_name(name, &list)
+/// Finds the colour with the closest name, see `colours::matching` for how names are compared.
+/// Ties go to the name that comes first alphabetically.
+pub fn get_nearest_colour_for_name(
+    name: &str,
+    colours: &[Colour],
+    tolerance: usize,
+) -> Option<Colour> {
+    get_closest_colours_for_name(name, colours, tolerance)
+        .into_iter()
+        .next()
 }
 
-pub fn get_nearest_colour_for_name(name: &str, colours: &[Colour]) -> Option<Colour> {
-    let compare_name = |other| edit_distance(&name, other);
+/// Finds every colour tied for the best match, sorted by name.
+pub fn get_closest_colours_for_name(
+    name: &str,
+    colours: &[Colour],
+    tolerance: usize,
+) -> Vec<Colour> {
+    get_closest_colours_with_aliases(name, colours, &[], tolerance)
+}
 
-    let (ending_distance, closest_colour) =
-        colours
+/// Same as `get_closest_colours_for_name`, but a colour also matches through any of its aliases.
+pub fn get_closest_colours_with_aliases(
+    name: &str,
+    colours: &[Colour],
+    aliases: &[ColourAlias],
+    tolerance: usize,
+) -> Vec<Colour> {
+    let compare_name = |colour: &Colour| {
+        aliases
             .iter()
-            .fold((usize::MAX, colours.get(0)?), |(distance, last), colour| {
-                let new_distance = compare_name(&colour.name);
-                if distance > new_distance {
-                    (new_distance, colour)
-                } else {
-                    (distance, last)
-                }
-            });
-
-    if ending_distance > MAX_STRING_COMPARE_DELTA {
-        None
-    } else {
-        Some(closest_colour.clone())
-    }
+            .filter(|alias| alias.colour_id == colour.id)
+            .map(|alias| alias.name.as_str())
+            .chain(iter::once(colour.name.as_str()))
+            .filter_map(|other| match_name(name, other, tolerance))
+            .min()
+    };
+
+    let best_match = match colours.iter().filter_map(|colour| compare_name(colour)).min() {
+        Some(best_match) => best_match,
+        None => return vec![],
+    };
+
+    let mut closest = colours
+        .iter()
+        .filter(|colour| compare_name(colour) == Some(best_match))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    closest.sort_by(|a, b| a.name.cmp(&b.name));
+
+    closest
+}
+
+/// Finds the colour whose role looks the most like the target, along with how far off it is.
+/// Colours with a missing role are skipped.
+pub fn find_nearest_to_colour(
+    target: &ParsedColour,
+    colours: &[Colour],
+    guild: &DiscordGuild,
+) -> Option<(Colour, f64)> {
+    colours
+        .iter()
+        .filter_map(|colour| {
+            let role = search_role(colour, guild)?;
+            let distance = target.compute_distance(&ParsedColour::from(role.colour));
+
+            Some((colour.clone(), distance))
+        }).min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(::std::cmp::Ordering::Equal))
 }
 
 /// Gets all the colours related to the guild.
@@ -99,13 +161,27 @@ pub fn convert_records_to_roles_and_name<'a>(
 }
 
 /// Converts names and roles gained from `convert_records_to_roles_and_name` into the format needed for the colour list image.
+/// `locked` holds the ids of colours with requirements, which get marked in the list.
 pub fn convert_roles_and_name_to_list_type(
     colours: &[(String, &DiscordRole)],
-) -> Vec<(Name, DiscordColour)> {
+    guild: &DiscordGuild,
+    locked: &[BigDecimal],
+) -> Vec<ListColour> {
     colours
         .iter()
-        .map(|&(ref name, ref role)| (Name(name.to_string()), role.colour))
-        .collect()
+        .map(|&(ref name, ref role)| ListColour {
+            name: Name(name.to_string()),
+            colour: role.colour,
+            added: role.id.0,
+            usage: guild
+                .members
+                .values()
+                .filter(|member| member.roles.contains(&role.id))
+                .count(),
+            locked: BigDecimal::from_u64(role.id.0)
+                .map(|id| locked.contains(&id))
+                .unwrap_or(false),
+        }).collect()
 }
 
 /// Finds a colour record from the role id.
@@ -129,6 +205,73 @@ pub fn remove_record(colour: &Colour, connection: &PgConnection) -> QueryResult<
     diesel::delete(colour).execute(connection)
 }
 
+/// Removes the colour for a role that was deleted outside of the bot, returning the removed record if there was one.
+pub fn remove_deleted_role(
+    guild: GuildId,
+    role: RoleId,
+    connection: &PgConnection,
+) -> Option<Colour> {
+    let colour = find_from_role_id(role, connection)?;
+
+    if colour.guild_id != BigDecimal::from_u64(guild.0)? {
+        return None;
+    }
+
+    remove_record(&colour, connection).ok().map(|_| colour)
+}
+
+/// Remembers the name and colour the bot is about to give a role.
+pub fn expect_role_edit(role: RoleId, name: String, colour: u32) {
+    ROLE_EDITS.insert(role, (name, colour));
+}
+
+/// Whether the role looks exactly like the bot's last edit left it, forgetting the edit either way.
+fn is_own_edit(role: &DiscordRole) -> bool {
+    ROLE_EDITS
+        .remove(&role.id)
+        .map(|(name, colour)| name == role.name && colour == role.colour.0)
+        .unwrap_or(false)
+}
+
+/// Picks up changes to a colour's role made in discord's role settings.
+/// The colour name follows the role name if the two were the same before the edit.
+/// Returns the colour when the colour list needs to be refreshed.
+pub fn sync_role_update(
+    old: Option<&DiscordRole>,
+    new: &DiscordRole,
+    connection: &PgConnection,
+) -> QueryResult<Option<Colour>> {
+    // the bot refreshes the list after its own edits already.
+    if is_own_edit(new) {
+        return Ok(None);
+    }
+
+    let colour = match find_from_role_id(new.id, connection) {
+        Some(colour) => colour,
+        None => return Ok(None),
+    };
+
+    // without the old role there's no telling what changed, so always refresh.
+    let (renamed, recoloured) = match old {
+        Some(old) => (
+            old.name != new.name && old.name == colour.name,
+            old.colour.0 != new.colour.0,
+        ),
+        None => (false, true),
+    };
+
+    if renamed {
+        diesel::update(&colour)
+            .set(c::name.eq(&new.name))
+            .get_result::<Colour>(connection)
+            .map(Some)
+    } else if recoloured {
+        Ok(Some(colour))
+    } else {
+        Ok(None)
+    }
+}
+
 pub fn remove_multiple(
     ids: Vec<BigDecimal>,
     guild: BigDecimal,
@@ -139,6 +282,21 @@ pub fn remove_multiple(
         .get_results(connection)
 }
 
+/// Creates the role for a new colour, the colour record still has to be saved.
+pub fn create_colour_role(
+    guild: &DiscordGuild,
+    name: &str,
+    colour: &ParsedColour,
+) -> Result<DiscordRole, SerenityError> {
+    let role_colour = colour.as_role_colour();
+
+    guild.create_role(|role| {
+        role.name(name)
+            .colour(u64::from(role_colour.0))
+            .mentionable(false)
+    })
+}
+
 /// Turns a role into a colour record that *can be* inserted into the database.
 /// *Note:*  this doesn't actually save the role, use `save_record_to_db` for that.
 pub fn convert_role_to_record_struct(
@@ -172,6 +330,98 @@ pub fn save_records_to_db(
         .get_results(connection)
 }
 
+/// A role that looks like a colour role from another bot, along with the name it would get on the list.
+#[derive(Clone, Debug)]
+pub struct AdoptableRole {
+    pub role: DiscordRole,
+    pub name: String,
+}
+
+/// Finds the roles that are only there for their colour: coloured, not managed by an integration,
+/// and granting nothing `@everyone` doesn't have already, since discord gives new roles the default permissions.
+/// Roles already on the colour list, and roles at or above the bot's highest role that it couldn't hand out, are left out.
+/// Ordered like the guild's role list.
+pub fn find_adoptable_roles(
+    guild: &DiscordGuild,
+    self_id: UserId,
+    existing: &[Colour],
+) -> Vec<AdoptableRole> {
+    let top_position = actions::setup::highest_role_position(guild, self_id);
+    let everyone = guild
+        .roles
+        .get(&RoleId(guild.id.0))
+        .map(|role| role.permissions)
+        .unwrap_or_else(Permissions::empty);
+    let existing_ids = existing
+        .iter()
+        .filter_map(|colour| colour.id.to_u64())
+        .collect::<Vec<_>>();
+    let mut taken = existing
+        .iter()
+        .map(|colour| colour.name.to_lowercase())
+        .collect::<Vec<_>>();
+
+    let mut roles = guild
+        .roles
+        .values()
+        .filter(|role| {
+            role.id.0 != guild.id.0
+                && !role.managed
+                && role.colour.0 != 0
+                && everyone.contains(role.permissions)
+                && role.position < top_position
+                && !existing_ids.contains(&role.id.0)
+        }).collect::<Vec<_>>();
+
+    roles.sort_by(|a, b| b.position.cmp(&a.position).then(a.id.cmp(&b.id)));
+
+    roles
+        .into_iter()
+        .map(|role| {
+            let name = propose_name(role, &taken);
+            taken.push(name.to_lowercase());
+
+            AdoptableRole {
+                role: role.clone(),
+                name,
+            }
+        }).collect()
+}
+
+/// Uses the role name when it has something to read, otherwise names it after the closest known colour.
+/// Names that are taken get a number on the end.
+fn propose_name(role: &DiscordRole, taken: &[String]) -> String {
+    let role_name = role.name.trim();
+
+    let name = if role_name.chars().any(char::is_alphanumeric) {
+        role_name.to_string()
+    } else {
+        ParsedColour::from(role.colour)
+            .find_name()
+            .unwrap_or_else(|| ParsedColour::from(role.colour).to_string())
+    };
+
+    iter::once(name.clone())
+        .chain((2..).map(|count| format!("{} ({})", name, count)))
+        .find(|name| !taken.contains(&name.to_lowercase()))
+        .expect("ran out of names")
+}
+
+/// Saves the adopted roles as colours, either all of them are saved or none are.
+pub fn adopt_roles(
+    roles: &[AdoptableRole],
+    guild: GuildId,
+    connection: &PgConnection,
+) -> QueryResult<Vec<Colour>> {
+    let records = roles
+        .iter()
+        .filter_map(|adopted| {
+            convert_role_to_record_struct(adopted.name.clone(), &adopted.role, guild)
+        }).collect::<Vec<_>>();
+
+    connection.transaction(|| save_records_to_db(&records, connection))
+}
+
 /// Simple function to give a discord member a discord role
 pub fn assign_role_to_user(
     member: &mut DiscordMember,
@@ -229,37 +479,64 @@ pub fn assign_colour_to_user(
     assign_role_to_user(&mut user_member, &colour_role)
 }
 
-/// generates the data for the colour image, and then returns a path to the image.
-pub fn generate_colour_image(
+/// generates the data for the colour list, and then renders each page of it in the guild's image format.
+/// These go to discord, so formats it can't show fall back to png.
+pub fn generate_colour_images(
     colours: &[Colour],
+    locked: &[BigDecimal],
     guild: &DiscordGuild,
-) -> Result<String, CommandError> {
+    settings: &GuildSettings,
+) -> Result<Vec<ColourListImage>, CommandError> {
+    let format = if settings.image_format.previews_in_discord() {
+        settings.image_format
+    } else {
+        OutputFormat::Png
+    };
+
+    generate_colour_images_as(colours, locked, guild, settings, format)
+}
+
+/// Same as `generate_colour_images`, but renders into the given format instead of the guild's.
+pub fn generate_colour_images_as(
+    colours: &[Colour],
+    locked: &[BigDecimal],
+    guild: &DiscordGuild,
+    settings: &GuildSettings,
+    format: OutputFormat,
+) -> Result<Vec<ColourListImage>, CommandError> {
     let roles_and_names = convert_records_to_roles_and_name(colours, &guild).ok_or_else(|| {
         CommandError(
             "Error generating list. Possible cause: No colours exist in the database or no colour channel exists.".to_string(),
         )
     })?;
 
-    let colour_list_data = convert_roles_and_name_to_list_type(&roles_and_names);
+    let colour_list_data = convert_roles_and_name_to_list_type(&roles_and_names, &guild, locked);
 
-    let colour_builder = ColourListBuilder::new();
+    let debug_dir = CONFIG
+        .debug
+        .as_ref()
+        .and_then(|debug| debug.image_dir.as_ref())
+        .map(PathBuf::from);
 
-    // TODO: Options and stuff, customization for the whole family!
+    let mut colour_builder = ColourListBuilder::new();
 
-    let id = guild.id;
+    colour_builder
+        .set_type(settings.list_style)
+        .set_sort_method(settings.sort_method)
+        .set_page_size(settings.page_size)
+        .set_format(format)
+        .set_theme(settings.theme.clone())
+        .set_columns(settings.columns)
+        .set_font(&settings.font_family, settings.font_size)
+        .set_show_hex_codes(settings.show_hex_codes)
+        .set_contrast_warnings(settings.contrast_warnings)
+        .set_debug_dir(debug_dir);
 
-    let colour_list_path = colour_builder
-        .create_image(&colour_list_data, id.0.to_string())
-        .map_err(|e| CommandError(format!("Failure generating colour image. Reason: {:?}", e)))?;
+    let id = guild.id;
 
-    colour_list_path
-        .to_str()
-        .map(str::to_string)
-        .ok_or_else(|| {
-            CommandError(
-                "The image path doesn't actually exist. This a bit of a f***up. Sorry.".to_string(),
-            )
-        })
+    colour_builder
+        .create_images(&colour_list_data, id.0.to_string())
+        .map_err(|e| CommandError(format!("Failure generating colour image. Reason: {:?}", e)))
 }
 
 pub struct UpdateActionParams<'a> {
@@ -283,17 +560,22 @@ pub fn update_colour_and_role<'a>(
     let role = search_role(&colour, guild)
         .ok_or_else(|| CommandError("Couldn't find the colour in the guild!".to_string()))?;
 
-    role.edit(|role_edit| {
-        role_edit
-            .name(match new_name {
-                Some(name) if change_role_name => name,
-                _ => &role.name,
-            }).colour(if let Some(colour) = new_colour {
-                u64::from(colour.as_role_colour().0)
-            } else {
-                u64::from(role.colour.0)
-            })
-    })?;
+    let role_name = match new_name {
+        Some(name) if change_role_name => name.to_string(),
+        _ => role.name.clone(),
+    };
+    let role_colour = match new_colour {
+        Some(colour) => colour.as_role_colour().0,
+        None => role.colour.0,
+    };
+
+    expect_role_edit(role.id, role_name.clone(), role_colour);
+
+    role.edit(|role_edit| role_edit.name(&role_name).colour(u64::from(role_colour)))
+        .map_err(|e| {
+            ROLE_EDITS.remove(&role.id);
+            e
+        })?;
 
     drop(role);
 
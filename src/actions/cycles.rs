@@ -0,0 +1,253 @@
+use failure::Error;
+
+use actions;
+use utils;
+
+use diesel;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+
+use constants::cycles::{MAX_CYCLE_COLOURS, MAX_CYCLE_INTERVAL, MIN_CYCLE_INTERVAL};
+use db::models::{Colour, Cycle, Guild, NewCycle};
+
+use db::schema::colours::dsl as c;
+use db::schema::colours::table as colours_table;
+use db::schema::cycles::dsl as y;
+use db::schema::cycles::table as cycles_table;
+
+use serenity::model::id::{GuildId, RoleId, UserId};
+use serenity::prelude::ModelError;
+use serenity::Error as SerenityError;
+
+use std::convert::TryFrom;
+
+use bigdecimal::BigDecimal;
+use num_traits::cast::{FromPrimitive, ToPrimitive};
+
+/// What a cycle changes the colour of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CycleTarget {
+    /// The user is handed each colour role in turn.
+    User(UserId),
+    /// The role has its colour changed to each colour in turn.
+    Role(RoleId),
+}
+
+impl CycleTarget {
+    /// The target a saved cycle belongs to, `None` if the row somehow has neither id.
+    pub fn of(cycle: &Cycle) -> Option<CycleTarget> {
+        match (&cycle.user_id, &cycle.role_id) {
+            (&Some(ref id), _) => id.to_u64().map(|id| CycleTarget::User(UserId(id))),
+            (_, &Some(ref id)) => id.to_u64().map(|id| CycleTarget::Role(RoleId(id))),
+            _ => None,
+        }
+    }
+
+    fn ids(self) -> (Option<BigDecimal>, Option<BigDecimal>) {
+        match self {
+            CycleTarget::User(user) => (BigDecimal::from_u64(user.0), None),
+            CycleTarget::Role(role) => (None, BigDecimal::from_u64(role.0)),
+        }
+    }
+}
+
+/// Gets every cycle running in the guild.
+pub fn find_for_guild(guild: &Guild, connection: &PgConnection) -> Vec<Cycle> {
+    Cycle::belonging_to(guild)
+        .order(y::id)
+        .load::<Cycle>(connection)
+        .unwrap_or_default()
+}
+
+/// Gets the cycle running for a user or role in the guild.
+pub fn find_for_target(
+    guild: &Guild,
+    target: CycleTarget,
+    connection: &PgConnection,
+) -> Option<Cycle> {
+    let query = Cycle::belonging_to(guild);
+
+    match target.ids() {
+        (Some(user), _) => query.filter(y::user_id.eq(user)).first(connection).ok(),
+        (_, Some(role)) => query.filter(y::role_id.eq(role)).first(connection).ok(),
+        _ => None,
+    }
+}
+
+/// Starts rotating the target through the colours, replacing any cycle it already had.
+/// The first colour is applied on the next check, after that one changes every `interval` seconds.
+pub fn start_cycle(
+    guild: &Guild,
+    target: CycleTarget,
+    colours: &[Colour],
+    interval: u64,
+    now: i64,
+    connection: &PgConnection,
+) -> Result<Cycle, Error> {
+    if interval < MIN_CYCLE_INTERVAL {
+        return Err(format_err!(
+            "Colours can change at most once every {}, discord would start rate limiting otherwise.",
+            utils::format_interval(MIN_CYCLE_INTERVAL)
+        ));
+    }
+
+    if interval > MAX_CYCLE_INTERVAL {
+        return Err(format_err!(
+            "Colours have to change at least once every {}.",
+            utils::format_interval(MAX_CYCLE_INTERVAL)
+        ));
+    }
+
+    if colours.len() < 2 || colours.len() > MAX_CYCLE_COLOURS {
+        return Err(format_err!(
+            "A cycle needs between 2 and {} colours.",
+            MAX_CYCLE_COLOURS
+        ));
+    }
+
+    let interval_seconds =
+        i64::try_from(interval).map_err(|_| format_err!("The interval is too long."))?;
+
+    let (user_id, role_id) = target.ids();
+    let new_cycle = NewCycle {
+        guild_id: guild.id.clone(),
+        user_id,
+        role_id,
+        colour_ids: colours.iter().map(|colour| colour.id.clone()).collect(),
+        interval_seconds,
+        next_run: now,
+    };
+
+    Ok(connection.transaction(|| {
+        stop_cycle(guild, target, connection)?;
+
+        diesel::insert_into(cycles_table)
+            .values(&new_cycle)
+            .get_result::<Cycle>(connection)
+    })?)
+}
+
+/// Stops the cycle for a user or role. Returns the amount of cycles stopped, 0 if there wasn't one.
+pub fn stop_cycle(guild: &Guild, target: CycleTarget, connection: &PgConnection) -> QueryResult<usize> {
+    match find_for_target(guild, target, connection) {
+        Some(cycle) => diesel::delete(&cycle).execute(connection),
+        None => Ok(0),
+    }
+}
+
+/// Gets the cycles that are due for a colour change.
+pub fn find_due_cycles(now: i64, connection: &PgConnection) -> QueryResult<Vec<Cycle>> {
+    cycles_table
+        .filter(y::next_run.le(now))
+        .order(y::next_run)
+        .load::<Cycle>(connection)
+}
+
+/// Gets the colours a cycle rotates through, in order, leaving out any that were deleted since.
+pub fn find_cycle_colours(cycle: &Cycle, connection: &PgConnection) -> Vec<Colour> {
+    let colours = colours_table
+        .filter(c::id.eq_any(&cycle.colour_ids))
+        .load::<Colour>(connection)
+        .unwrap_or_default();
+
+    cycle
+        .colour_ids
+        .iter()
+        .filter_map(|id| colours.iter().find(|colour| colour.id == *id).cloned())
+        .collect()
+}
+
+/// Moves the cycle on to its next colour and schedules the next run.
+pub fn advance_cycle(
+    cycle: &Cycle,
+    colour_count: usize,
+    now: i64,
+    connection: &PgConnection,
+) -> QueryResult<Cycle> {
+    let position = (cycle.position as usize + 1) % colour_count.max(1);
+    // cycles saved before the interval had an upper bound could hold anything, keep them within the limits.
+    let interval = cycle
+        .interval_seconds
+        .max(MIN_CYCLE_INTERVAL as i64)
+        .min(MAX_CYCLE_INTERVAL as i64);
+    let next_run = now.checked_add(interval).unwrap_or_else(i64::max_value);
+
+    diesel::update(cycle)
+        .set((y::position.eq(position as i32), y::next_run.eq(next_run)))
+        .get_result::<Cycle>(connection)
+}
+
+/// Applies a colour to the cycle's target, using the cached guild.
+fn apply_colour(cycle: &Cycle, colour: &Colour, connection: &PgConnection) -> Result<(), SerenityError> {
+    let guild = cycle
+        .guild_id
+        .to_u64()
+        .and_then(|id| GuildId(id).find())
+        .ok_or(SerenityError::Model(ModelError::GuildNotFound))?;
+
+    let colour_role = match actions::colours::search_role(colour, &guild.read()) {
+        Some(role) => role,
+        // the colour lost its role, skip over it until it's cleaned up.
+        None => return Ok(()),
+    };
+
+    match CycleTarget::of(cycle) {
+        Some(CycleTarget::User(user)) => {
+            let user = guild
+                .read()
+                .members
+                .get(&user)
+                .map(|member| member.user.read().clone())
+                .ok_or(SerenityError::Model(ModelError::InvalidUser))?;
+
+            actions::colours::assign_colour_to_user(&user, guild.write(), &colour_role, connection)
+        }
+        Some(CycleTarget::Role(role)) => {
+            let role = guild
+                .read()
+                .roles
+                .get(&role)
+                .cloned()
+                .ok_or(SerenityError::Model(ModelError::ItemMissing))?;
+
+            role.edit(|edit| edit.colour(u64::from(colour_role.colour.0)))
+                .map(|_| ())
+        }
+        None => Err(SerenityError::Model(ModelError::ItemMissing)),
+    }
+}
+
+/// Changes the colour of every cycle that's due, then schedules their next run.
+/// Cycles whose target or colours are gone are stopped. Returns the amount of cycles that ran.
+pub fn run_due_cycles(now: i64, connection: &PgConnection) -> QueryResult<usize> {
+    let mut ran = 0;
+
+    for cycle in find_due_cycles(now, connection)? {
+        let colours = find_cycle_colours(&cycle, connection);
+
+        let colour = match colours.get(cycle.position as usize % colours.len().max(1)) {
+            Some(colour) => colour,
+            None => {
+                diesel::delete(&cycle).execute(connection)?;
+                continue;
+            }
+        };
+
+        match apply_colour(&cycle, colour, connection) {
+            // the guild isn't cached yet right after a restart, try again on the next check.
+            Err(SerenityError::Model(ModelError::GuildNotFound)) => continue,
+            Err(SerenityError::Model(ModelError::InvalidUser))
+            | Err(SerenityError::Model(ModelError::ItemMissing)) => {
+                diesel::delete(&cycle).execute(connection)?;
+                continue;
+            }
+            // anything else is likely a hiccup on discord's end, keep the schedule going.
+            _ => (),
+        }
+
+        advance_cycle(&cycle, colours.len(), now, connection)?;
+        ran += 1;
+    }
+
+    Ok(ran)
+}
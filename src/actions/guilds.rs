@@ -1,5 +1,3 @@
-use std::fs;
-
 use failure::Error;
 
 use actions;
@@ -9,8 +7,14 @@ use diesel::pg::PgConnection;
 use diesel::prelude::*;
 use diesel::result::Error as DieselError;
 
-use db::models::{Colour, Guild};
+use colours::images::ColourListImage;
+use db::models::{Colour, Guild, GuildSettings};
+
+use constants::commands::DEFAULT_PREFIXES;
+use constants::guilds::DEFAULT_PURGE_GRACE_DAYS;
 
+use db::schema::colours::dsl as c;
+use db::schema::colours::table as colours_table;
 use db::schema::guilds::dsl as g;
 use db::schema::guilds::table as guilds_table;
 
@@ -20,6 +24,9 @@ use serenity::model::id::{ChannelId, GuildId, MessageId};
 use serenity::model::user::User as DiscordUser;
 use serenity::prelude::ModelError;
 use serenity::Error as SerenityError;
+use serenity::CACHE;
+
+use CONFIG;
 
 use parking_lot::RwLockReadGuard;
 
@@ -101,6 +108,99 @@ pub fn update_channel_id(
         .get_result::<Guild>(connection)?)
 }
 
+/// Writes the given settings back into the `settings` column for the guild.
+pub fn update_settings(
+    guild: &Guild,
+    settings: &GuildSettings,
+    connection: &PgConnection,
+) -> Result<Guild, Error> {
+    Ok(diesel::update(guilds_table.find(&guild.id))
+        .set(g::settings.eq(settings.to_value()))
+        .get_result::<Guild>(connection)?)
+}
+
+/// Stops tracking a colour channel that was deleted, returning the guild that was using it.
+pub fn clear_deleted_channel(
+    channel: ChannelId,
+    connection: &PgConnection,
+) -> QueryResult<Option<Guild>> {
+    let id = BigDecimal::from_u64(channel.0).ok_or(DieselError::NotFound)?;
+    let no_messages: Vec<BigDecimal> = vec![];
+
+    diesel::update(guilds_table.filter(g::channel_id.eq(id)))
+        .set((
+            g::channel_id.eq(None::<BigDecimal>),
+            g::list_message_ids.eq(no_messages),
+        )).get_result::<Guild>(connection)
+        .optional()
+}
+
+/// Days to keep a guild's records after the bot gets removed, as set in the config.
+pub fn purge_grace_days() -> u64 {
+    CONFIG
+        .retention
+        .as_ref()
+        .and_then(|retention| retention.purge_grace_days)
+        .unwrap_or(DEFAULT_PURGE_GRACE_DAYS)
+}
+
+/// Marks a guild's records to be deleted once the grace period is over.
+pub fn schedule_purge(guild: GuildId, purge_at: i64, connection: &PgConnection) -> QueryResult<usize> {
+    let id = BigDecimal::from_u64(guild.0).ok_or(DieselError::NotFound)?;
+
+    diesel::update(guilds_table.find(id))
+        .set(g::purge_at.eq(Some(purge_at)))
+        .execute(connection)
+}
+
+/// Keeps a guild's records around again, for when the bot is invited back during the grace period.
+pub fn cancel_purge(guild: GuildId, connection: &PgConnection) -> QueryResult<usize> {
+    let id = BigDecimal::from_u64(guild.0).ok_or(DieselError::NotFound)?;
+
+    diesel::update(guilds_table.find(id).filter(g::purge_at.is_not_null()))
+        .set(g::purge_at.eq(None::<i64>))
+        .execute(connection)
+}
+
+/// Deletes every guild, and its colours, whose grace period ended before `now`.
+/// Returns the amount of guilds deleted.
+pub fn purge_expired_guilds(now: i64, connection: &PgConnection) -> QueryResult<usize> {
+    connection.transaction(|| {
+        let expired = guilds_table
+            .filter(g::purge_at.le(now))
+            .select(g::id)
+            .load::<BigDecimal>(connection)?;
+
+        diesel::delete(colours_table.filter(c::guild_id.eq_any(&expired))).execute(connection)?;
+
+        diesel::delete(guilds_table.filter(g::id.eq_any(&expired))).execute(connection)
+    })
+}
+
+/// Gets the prefixes a guild answers to, or the default prefixes if the guild has no record.
+pub fn get_prefixes_for_guild(guild: GuildId, connection: &PgConnection) -> Vec<String> {
+    convert_guild_to_record(guild, connection)
+        .map(|record| record.settings().active_prefixes())
+        .unwrap_or_else(|| DEFAULT_PREFIXES.iter().map(|p| p.to_string()).collect())
+}
+
+/// Finds which prefix a message starts with, ignoring case.
+/// Longer prefixes are checked first so `!colour` doesn't get read as `!c` + `olour`.
+/// The prefix is returned as it was typed in the message.
+pub fn match_prefix(content: &str, prefixes: &[String]) -> Option<String> {
+    let mut prefixes = prefixes.to_vec();
+    prefixes.sort_by(|a, b| b.len().cmp(&a.len()));
+
+    prefixes
+        .iter()
+        .filter_map(|prefix| {
+            content
+                .get(..prefix.len())
+                .filter(|start| start.to_lowercase() == prefix.to_lowercase())
+                .map(str::to_string)
+        }).next()
+}
+
 /// Converts a discord user model into a discord member
 pub fn convert_user_to_member_result<'a>(
     user: &DiscordUser,
@@ -112,6 +212,57 @@ pub fn convert_user_to_member_result<'a>(
         .ok_or(ModelError::InvalidUser)
 }
 
+/// Saves the ids of the messages holding the colour list, so the next refresh can replace all of them.
+pub fn update_list_messages(
+    guild: &Guild,
+    messages: &[MessageId],
+    connection: &PgConnection,
+) -> Result<Guild, Error> {
+    let ids = messages
+        .iter()
+        .filter_map(|id| BigDecimal::from_u64(id.0))
+        .collect::<Vec<_>>();
+
+    Ok(diesel::update(guilds_table.find(&guild.id))
+        .set(g::list_message_ids.eq(ids))
+        .get_result::<Guild>(connection)?)
+}
+
+/// Posts each page of the colour list, the help message goes along with the last page.
+fn post_list_pages(
+    channel: ChannelId,
+    images: &[ColourListImage],
+    help_message: &str,
+) -> Result<Vec<MessageId>, SerenityError> {
+    let last_page = images.len().saturating_sub(1);
+
+    images
+        .iter()
+        .enumerate()
+        .map(|(page, image)| {
+            channel
+                .send_files(vec![image.as_attachment()], |msg| {
+                    if page == last_page {
+                        msg.content(help_message)
+                    } else {
+                        msg
+                    }
+                }).map(|msg| msg.id)
+        }).collect()
+}
+
+/// Refreshes the colour list for a guild in the cache, for events that only come with the guild id.
+pub fn refresh_channel_message(guild: GuildId, connection: &PgConnection) -> Result<(), CommandError> {
+    let guild = guild
+        .find()
+        .ok_or_else(|| CommandError("The guild isn't in the cache.".to_string()))?;
+
+    let self_id = CACHE.read().user.id.0;
+    let guild = guild.read();
+
+    update_channel_message(&guild, self_id, connection, false)
+}
+
 /// updates the help message and colour list in the colour channel.
 pub fn update_channel_message(
     guild: &RwLockReadGuard<DiscordGuild>,
@@ -125,50 +276,78 @@ pub fn update_channel_message(
     let colours = actions::colours::find_all(&guild_record, connection)
         .ok_or_else(|| CommandError("Error trying to get list of colours.".to_string()))?;
 
-    let path = actions::colours::generate_colour_image(&colours, &guild)?;
+    let locked = actions::requirements::find_locked_ids(&guild_record, connection);
+
+    let images = actions::colours::generate_colour_images(
+        &colours,
+        &locked,
+        &guild,
+        &guild_record.settings(),
+    )?;
 
     let channel_id_result = guild_record
         .channel_id
+        .as_ref()
         .and_then(|id| id.to_u64())
         .map(ChannelId);
 
-    if loudly_fail {
-        channel_id_result.ok_or_else(|| { 
-            let _ = fs::remove_file(&path);  
-            CommandError("This server does not have a colour channel set! Add a channel with the `setchannel` command!".to_string()) 
-        })?;
-    } else if let Some(ch) = channel_id_result {
+    if loudly_fail && channel_id_result.is_none() {
+        return Err(CommandError("This server does not have a colour channel set! Add a channel with the `setchannel` command!".to_string()));
+    }
+
+    if let Some(ch) = channel_id_result {
+        // pickers are edited in place so members keep their reactions, the list gets reposted under them.
+        let keep_pickers = guild_record.settings().reaction_roles;
+        let pickers = actions::pickers::find_picker_messages(&guild_record, connection);
+        let is_kept = |id: &MessageId| keep_pickers && pickers.contains(id);
+
         let old_messages = ch
             .messages(|filter| filter.limit(50))?
             .iter()
-            .filter(|msg| msg.author.id.0 == self_id)
+            .filter(|msg| msg.author.id.0 == self_id && !is_kept(&msg.id))
             .map(|msg| msg.id)
             .collect::<Vec<MessageId>>();
 
-        if !old_messages.is_empty() {
-            for msg in old_messages {
-                ch.delete_message(msg)?;
-            }
+        for msg in &old_messages {
+            ch.delete_message(*msg)?;
         }
 
-        ch.send_files(vec![path.as_str()], |msg| {
-            let names = colours
-                .iter()
-                .map(|&Colour { ref name, .. }| name.clone())
-                .collect::<Vec<_>>();
+        // pages and pickers from the last refresh can be older than the last 50 messages, they may also have been deleted by hand.
+        let tracked_messages = guild_record
+            .list_message_ids
+            .iter()
+            .filter_map(|id| id.to_u64())
+            .map(MessageId)
+            .chain(pickers.iter().cloned())
+            .filter(|id| !old_messages.contains(id) && !is_kept(id))
+            .collect::<Vec<MessageId>>();
 
-            let help_message = actions::channel_help::generate_help_message(&names);
+        for msg in tracked_messages {
+            let _ = ch.delete_message(msg);
+        }
 
-            msg.content(help_message)
-        }).and_then(|_| {
-            fs::remove_file(&path).map_err(|_| {
-                SerenityError::Other("Error trying to delete the leftover colour image")
-            })
-        })?;
-    }
+        let names = colours
+            .iter()
+            .map(|&Colour { ref name, .. }| name.clone())
+            .collect::<Vec<_>>();
+
+        let prefixes = guild_record.settings().active_prefixes();
+        let prefix = prefixes.first().map(String::as_str).unwrap_or(DEFAULT_PREFIXES[0]);
+
+        let help_message = actions::channel_help::generate_help_message(&names, prefix);
 
-    if fs::File::open(&path).is_ok() {
-        let _ = fs::remove_file(&path);
+        let posted = post_list_pages(ch, &images, &help_message)?;
+
+        update_list_messages(&guild_record, &posted, connection)
+            .map_err(|e| CommandError(format!("Could not save the colour list messages due to {}", e)))?;
+
+        if keep_pickers {
+            actions::pickers::sync_pickers(ch, &guild_record, &colours, connection)?;
+        } else {
+            actions::pickers::save_pickers(&guild_record, &[], connection).map_err(|e| {
+                CommandError(format!("Could not clear the colour pickers due to {}", e))
+            })?;
+        }
     }
 
     Ok(())
@@ -2,7 +2,14 @@
 /// These are split up into functions that perform the **LEAST** amount of actions needed, like changing a DB record to a discord role.
 /// These functions should be easily composible so they can be chained togeather in the command section.
 /// No monolith functions! All these functions should be their own isolated contexts.
+pub mod aliases;
 pub mod channel_help;
 pub mod colours;
+pub mod cycles;
 pub mod guilds;
+pub mod palettes;
+pub mod pickers;
+pub mod requirements;
+pub mod setup;
+pub mod temporary;
 mod tests;
@@ -0,0 +1,485 @@
+use failure::Error;
+
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use actions;
+
+use diesel::pg::PgConnection;
+
+use colours::models::ParsedColour;
+use db::models::{Colour, Guild};
+
+use serenity::model::guild::{Guild as DiscordGuild, Role as DiscordRole};
+
+use serde_json;
+
+/// Limits so an import can't flood the guild with roles, discord stops at 250 anyway.
+pub const MAX_IMPORT_COLOURS: usize = 100;
+pub const MAX_IMPORT_SIZE: u64 = 256 * 1024;
+
+const CSV_HEADER: &str = "name,hex,aliases,role_name";
+
+/// A colour as it's written to and read from a palette file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaletteEntry {
+    pub name: String,
+    pub hex: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Only kept when it differs from the name, imports name the role after the colour otherwise.
+    #[serde(default)]
+    pub role_name: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaletteFormat {
+    Json,
+    Csv,
+}
+
+impl PaletteFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            PaletteFormat::Json => "json",
+            PaletteFormat::Csv => "csv",
+        }
+    }
+
+    /// Works the format out from a file name, like `colours.csv`.
+    pub fn from_filename(filename: &str) -> Option<Self> {
+        filename.rsplit('.').next().and_then(|ext| ext.parse().ok())
+    }
+}
+
+impl FromStr for PaletteFormat {
+    type Err = Error;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format.trim().to_lowercase().as_str() {
+            "json" => Ok(PaletteFormat::Json),
+            "csv" => Ok(PaletteFormat::Csv),
+            other => Err(format_err!(
+                "\"{}\" isn't a palette format, use `json` or `csv`.",
+                other
+            )),
+        }
+    }
+}
+
+/// What to do with a colour in the file when the guild already has a colour by that name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    Skip,
+    /// Imports it under a free name, like "Red (2)".
+    Rename,
+    /// Changes the existing colour to the one in the file.
+    Overwrite,
+}
+
+impl Default for ConflictPolicy {
+    fn default() -> Self {
+        ConflictPolicy::Skip
+    }
+}
+
+impl FromStr for ConflictPolicy {
+    type Err = Error;
+
+    fn from_str(policy: &str) -> Result<Self, Self::Err> {
+        match policy.trim().to_lowercase().as_str() {
+            "skip" => Ok(ConflictPolicy::Skip),
+            "rename" => Ok(ConflictPolicy::Rename),
+            "overwrite" | "replace" => Ok(ConflictPolicy::Overwrite),
+            other => Err(format_err!(
+                "\"{}\" isn't a way to handle existing colours, use `skip`, `rename` or `overwrite`.",
+                other
+            )),
+        }
+    }
+}
+
+/// What an import does with one of the colours in the file.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ImportAction {
+    /// Creates a role and colour called `name`, which is only different from the entry's name when renamed.
+    Create { entry: PaletteEntry, name: String },
+    Overwrite { entry: PaletteEntry, existing: Colour },
+    Skip { entry: PaletteEntry },
+}
+
+impl ImportAction {
+    pub fn entry(&self) -> &PaletteEntry {
+        match *self {
+            ImportAction::Create { ref entry, .. }
+            | ImportAction::Overwrite { ref entry, .. }
+            | ImportAction::Skip { ref entry } => entry,
+        }
+    }
+
+    /// A few words for the preview table.
+    pub fn describe(&self) -> String {
+        match *self {
+            ImportAction::Create { ref entry, ref name } if *name != entry.name => {
+                format!("create as \"{}\"", name)
+            }
+            ImportAction::Create { .. } => "create".to_string(),
+            ImportAction::Overwrite { .. } => "overwrite".to_string(),
+            ImportAction::Skip { .. } => "skip, name taken".to_string(),
+        }
+    }
+}
+
+/// Gathers every colour in the guild along with its aliases. Colours whose role is gone are left out.
+pub fn build_palette(
+    colours: &[Colour],
+    guild: &DiscordGuild,
+    connection: &PgConnection,
+) -> Vec<PaletteEntry> {
+    colours
+        .iter()
+        .filter_map(|colour| {
+            let role = actions::colours::search_role(colour, guild)?;
+
+            Some(PaletteEntry {
+                name: colour.name.clone(),
+                hex: ParsedColour::from(role.colour).to_string(),
+                aliases: actions::aliases::find_for_colour(colour, connection),
+                role_name: if role.name == colour.name {
+                    None
+                } else {
+                    Some(role.name)
+                },
+            })
+        }).collect()
+}
+
+/// Writes the palette out as a file.
+pub fn export_palette(entries: &[PaletteEntry], format: PaletteFormat) -> Result<String, Error> {
+    match format {
+        PaletteFormat::Json => Ok(serde_json::to_string_pretty(entries)?),
+        PaletteFormat::Csv => {
+            let mut lines = vec![CSV_HEADER.to_string()];
+
+            lines.extend(entries.iter().map(|entry| {
+                [
+                    entry.name.as_str(),
+                    entry.hex.as_str(),
+                    &entry.aliases.join(";"),
+                    entry.role_name.as_ref().map(String::as_str).unwrap_or(""),
+                ].iter()
+                .map(|field| escape_csv_field(field))
+                .collect::<Vec<_>>()
+                .join(",")
+            }));
+
+            Ok(lines.join("\n"))
+        }
+    }
+}
+
+/// Reads a palette file, making sure every colour in it can be understood.
+pub fn parse_palette(data: &str, format: PaletteFormat) -> Result<Vec<PaletteEntry>, Error> {
+    let entries = match format {
+        PaletteFormat::Json => serde_json::from_str::<Vec<PaletteEntry>>(data)
+            .map_err(|e| format_err!("Couldn't read the file as JSON: {}", e))?,
+        PaletteFormat::Csv => parse_csv(data)?,
+    };
+
+    if entries.len() > MAX_IMPORT_COLOURS {
+        return Err(format_err!(
+            "The file has {} colours, only {} can be imported at once.",
+            entries.len(),
+            MAX_IMPORT_COLOURS
+        ));
+    }
+
+    entries
+        .into_iter()
+        .enumerate()
+        .map(|(index, mut entry)| {
+            entry.name = entry.name.trim().to_string();
+
+            if entry.name.is_empty() {
+                return Err(format_err!("Colour {} in the file has no name.", index + 1));
+            }
+
+            let colour = entry.hex.parse::<ParsedColour>().map_err(|_| {
+                format_err!(
+                    "Couldn't read \"{}\" as a colour for {}.",
+                    entry.hex,
+                    entry.name
+                )
+            })?;
+
+            entry.hex = colour.to_string();
+            entry.role_name = entry
+                .role_name
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty());
+
+            Ok(entry)
+        }).collect()
+}
+
+/// Works out what happens to each colour in the file, without changing anything.
+/// Colours later in the file that share a name with an earlier one are treated like existing colours.
+pub fn plan_import(
+    entries: Vec<PaletteEntry>,
+    existing: &[Colour],
+    policy: ConflictPolicy,
+) -> Vec<ImportAction> {
+    let mut taken = existing
+        .iter()
+        .map(|colour| colour.name.to_lowercase())
+        .collect::<HashSet<_>>();
+    let mut overwritten = HashSet::new();
+
+    entries
+        .into_iter()
+        .map(|entry| {
+            let lowercase = entry.name.to_lowercase();
+
+            if !taken.contains(&lowercase) {
+                taken.insert(lowercase);
+
+                return ImportAction::Create {
+                    name: entry.name.clone(),
+                    entry,
+                };
+            }
+
+            let existing = existing
+                .iter()
+                .find(|colour| colour.name.to_lowercase() == lowercase);
+
+            match (policy, existing) {
+                (ConflictPolicy::Rename, _) => {
+                    let name = (2..)
+                        .map(|count| format!("{} ({})", entry.name, count))
+                        .find(|name| !taken.contains(&name.to_lowercase()))
+                        .expect("ran out of names");
+
+                    taken.insert(name.to_lowercase());
+
+                    ImportAction::Create { entry, name }
+                }
+                (ConflictPolicy::Overwrite, Some(existing)) if overwritten.insert(lowercase.clone()) => {
+                    ImportAction::Overwrite {
+                        entry,
+                        existing: existing.clone(),
+                    }
+                }
+                _ => ImportAction::Skip { entry },
+            }
+        }).collect()
+}
+
+/// Carries out an import. Overwrites are checked before anything is changed, then roles are created
+/// and the colours saved together, if saving fails the new roles are deleted again.
+/// Returns the amount of colours created and overwritten.
+pub fn apply_import(
+    plan: &[ImportAction],
+    guild: &DiscordGuild,
+    guild_record: &Guild,
+    connection: &PgConnection,
+) -> Result<(usize, usize), Error> {
+    // an overwrite that can't happen shouldn't leave the new colours behind.
+    for action in plan {
+        if let ImportAction::Overwrite { ref existing, .. } = *action {
+            if actions::colours::search_role(existing, guild).is_none() {
+                return Err(format_err!(
+                    "Couldn't find the role for {}, nothing was imported.",
+                    existing.name
+                ));
+            }
+        }
+    }
+
+    let mut created_roles = vec![];
+    let mut records = vec![];
+
+    for action in plan {
+        if let ImportAction::Create {
+            ref entry,
+            ref name,
+        } = *action
+        {
+            let colour = entry.hex.parse::<ParsedColour>()?;
+            let role_name = entry.role_name.as_ref().unwrap_or(name);
+
+            let role = match actions::colours::create_colour_role(guild, role_name, &colour) {
+                Ok(role) => role,
+                Err(e) => {
+                    delete_roles(&created_roles);
+                    return Err(format_err!("Couldn't create the role for {}: {}", name, e));
+                }
+            };
+
+            let record =
+                actions::colours::convert_role_to_record_struct(name.clone(), &role, guild.id);
+            created_roles.push(role);
+
+            match record {
+                Some(record) => records.push(record),
+                None => {
+                    delete_roles(&created_roles);
+                    return Err(format_err!("Couldn't convert the role for {}.", name));
+                }
+            }
+        }
+    }
+
+    let saved = actions::colours::save_records_to_db(&records, connection).map_err(|e| {
+        delete_roles(&created_roles);
+        format_err!("Couldn't save the colours due to {}", e)
+    })?;
+
+    for action in plan {
+        if let ImportAction::Create {
+            ref entry,
+            ref name,
+        } = *action
+        {
+            if let Some(colour) = saved.iter().find(|colour| colour.name == *name) {
+                add_aliases(colour, &entry.aliases, guild_record, connection);
+            }
+        }
+    }
+
+    let mut overwritten = 0;
+
+    for action in plan {
+        if let ImportAction::Overwrite {
+            ref entry,
+            ref existing,
+        } = *action
+        {
+            let colour = entry.hex.parse::<ParsedColour>()?;
+
+            // the new colours are saved by now, so say how far the import got.
+            actions::colours::update_colour_and_role(
+                actions::colours::UpdateActionParams {
+                    colour: existing.clone(),
+                    new_colour: Some(colour),
+                    new_name: None,
+                    change_role_name: false,
+                    guild,
+                },
+                connection,
+            ).map_err(|e| {
+                format_err!(
+                    "Couldn't overwrite {}: {}\n{} colours were created and {} overwritten before that.",
+                    existing.name,
+                    e.0,
+                    saved.len(),
+                    overwritten
+                )
+            })?;
+
+            add_aliases(existing, &entry.aliases, guild_record, connection);
+            overwritten += 1;
+        }
+    }
+
+    Ok((saved.len(), overwritten))
+}
+
+/// Aliases that clash with another colour are dropped, the colour itself is more important.
+fn add_aliases(colour: &Colour, aliases: &[String], guild: &Guild, connection: &PgConnection) {
+    for alias in aliases {
+        let _ = actions::aliases::add_alias(colour, alias, guild, connection);
+    }
+}
+
+fn delete_roles(roles: &[DiscordRole]) {
+    for role in roles {
+        let _ = role.delete();
+    }
+}
+
+fn escape_csv_field(field: &str) -> String {
+    if field.contains(|c| c == ',' || c == '"' || c == '\n' || c == '\r') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Splits csv into rows of fields. Quoted fields can hold commas, doubled quotes and line breaks,
+/// so the rows can't be split on lines up front.
+fn split_csv(data: &str) -> Vec<Vec<String>> {
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = data.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => row.push(::std::mem::replace(&mut field, String::new())),
+            '\r' if !quoted => (),
+            '\n' if !quoted => {
+                row.push(::std::mem::replace(&mut field, String::new()));
+                rows.push(::std::mem::replace(&mut row, vec![]));
+            }
+            c => field.push(c),
+        }
+    }
+
+    row.push(field);
+    rows.push(row);
+
+    // blank lines come out as a single empty field.
+    rows.into_iter()
+        .filter(|row| row.len() > 1 || row.iter().any(|field| !field.trim().is_empty()))
+        .collect()
+}
+
+fn parse_csv(data: &str) -> Result<Vec<PaletteEntry>, Error> {
+    // excel and google sheets start their csv files with a byte order mark.
+    let data = data.trim_left_matches('\u{feff}');
+    let mut rows = split_csv(data).into_iter().peekable();
+
+    // the header is optional, but the columns have to be in the usual order.
+    if rows
+        .peek()
+        .and_then(|row| row.first())
+        .map(|field| field.trim().eq_ignore_ascii_case("name"))
+        .unwrap_or(false)
+    {
+        rows.next();
+    }
+
+    rows
+        .enumerate()
+        .map(|(index, row)| {
+            let mut fields = row.into_iter();
+
+            let name = fields.next().unwrap_or_default();
+            let hex = fields
+                .next()
+                .ok_or_else(|| format_err!("Row {} of the file has no colour.", index + 1))?;
+            let aliases = fields
+                .next()
+                .unwrap_or_default()
+                .split(';')
+                .map(str::trim)
+                .filter(|alias| !alias.is_empty())
+                .map(str::to_string)
+                .collect();
+            let role_name = fields.next();
+
+            Ok(PaletteEntry {
+                name,
+                hex: hex.trim().to_string(),
+                aliases,
+                role_name,
+            })
+        }).collect()
+}
@@ -0,0 +1,465 @@
+use actions;
+use utils;
+
+use diesel;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+
+use db::models::{Colour, ColourReaction, Guild, PickerMessage};
+
+use db::schema::colour_reactions::dsl as r;
+use db::schema::colour_reactions::table as reactions_table;
+use db::schema::colours;
+use db::schema::colours::table as colours_table;
+use db::schema::picker_messages::dsl as p;
+use db::schema::picker_messages::table as pickers_table;
+
+use serenity::model::channel::{Message, Reaction, ReactionType};
+use serenity::model::id::{ChannelId, GuildId, MessageId, RoleId};
+use serenity::prelude::ModelError;
+use serenity::Error as SerenityError;
+
+use bigdecimal::BigDecimal;
+use num_traits::cast::{FromPrimitive, ToPrimitive};
+
+/// Discord only allows this many different reactions on a single message.
+pub const MAX_PICKER_REACTIONS: usize = 20;
+
+/// The emojis handed out to the colours on each picker message, :regional_indicator_a: to :regional_indicator_t:.
+pub const PICKER_EMOJIS: [&str; MAX_PICKER_REACTIONS] = [
+    "🇦", "🇧", "🇨", "🇩", "🇪", "🇫", "🇬", "🇭", "🇮", "🇯", "🇰", "🇱", "🇲", "🇳", "🇴", "🇵", "🇶",
+    "🇷", "🇸", "🇹",
+];
+
+/// The colours on a single picker message, along with the emoji each one is bound to.
+pub type PickerPage = Vec<(Colour, &'static str)>;
+
+/// Splits the colours into picker messages, in name order, and binds an emoji to each one.
+pub fn assign_emojis(colours: &[Colour]) -> Vec<PickerPage> {
+    let mut colours = colours.to_vec();
+    colours.sort_by_key(|colour| colour.name.to_lowercase());
+
+    colours
+        .chunks(MAX_PICKER_REACTIONS)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .cloned()
+                .zip(PICKER_EMOJIS.iter().cloned())
+                .collect()
+        }).collect()
+}
+
+/// The text of a picker message, listing which emoji gives out which colour.
+pub fn generate_picker_message(page: &[(Colour, &str)]) -> String {
+    let bindings = page
+        .iter()
+        .map(|&(ref colour, emoji)| format!("{} {}", emoji, colour.name))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        "React to get a colour, remove your reaction to take it off again.\n\n{}",
+        bindings
+    )
+}
+
+/// The text used to store a reaction's emoji, custom emojis are kept in the `<:name:id>` format.
+pub fn emoji_key(emoji: &ReactionType) -> String {
+    match *emoji {
+        ReactionType::Custom {
+            id, ref name, ..
+        } => format!("<:{}:{}>", name.as_ref().map(String::as_str).unwrap_or(""), id.0),
+        ReactionType::Unicode(ref emoji) => emoji.clone(),
+    }
+}
+
+/// A picker message as it should look after a refresh, the id is `None` for messages that still have to be posted.
+pub type PlannedPicker = (Option<MessageId>, PickerPage);
+
+/// Works out the picker messages for the colours, starting from the ones already posted.
+/// Colours keep the message and emoji they had, so the reactions members left keep pointing at the same colour.
+/// New colours fill the free emojis first, whatever doesn't fit goes on new messages.
+/// Messages that end up with no colours are planned with an empty page, so they can be deleted.
+pub fn plan_pickers(
+    colours: &[Colour],
+    existing: &[(MessageId, Vec<ColourReaction>)],
+) -> Vec<PlannedPicker> {
+    let mut pickers = existing
+        .iter()
+        .map(|&(message, ref bindings)| {
+            let page = PICKER_EMOJIS
+                .iter()
+                .filter_map(|emoji| {
+                    let binding = bindings.iter().find(|binding| binding.emoji == *emoji)?;
+
+                    colours
+                        .iter()
+                        .find(|colour| colour.id == binding.colour_id)
+                        .map(|colour| (colour.clone(), *emoji))
+                }).collect::<PickerPage>();
+
+            (Some(message), page)
+        }).collect::<Vec<PlannedPicker>>();
+
+    let mut unplaced = {
+        let is_placed = |colour: &Colour| {
+            pickers
+                .iter()
+                .any(|&(_, ref page)| page.iter().any(|&(ref placed, _)| placed.id == colour.id))
+        };
+
+        colours
+            .iter()
+            .filter(|colour| !is_placed(colour))
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+    unplaced.sort_by_key(|colour| colour.name.to_lowercase());
+
+    let mut overflow = vec![];
+
+    'colours: for colour in unplaced {
+        for &mut (_, ref mut page) in pickers.iter_mut() {
+            let free = PICKER_EMOJIS
+                .iter()
+                .cloned()
+                .find(|emoji| !page.iter().any(|&(_, used)| used == *emoji));
+
+            if let Some(emoji) = free {
+                page.push((colour, emoji));
+                page.sort_by_key(|&(_, emoji)| PICKER_EMOJIS.iter().position(|used| *used == emoji));
+
+                continue 'colours;
+            }
+        }
+
+        overflow.push(colour);
+    }
+
+    pickers.extend(assign_emojis(&overflow).into_iter().map(|page| (None, page)));
+    pickers
+}
+
+/// Gets the guild's picker messages in the order they were posted, along with the emojis bound on each.
+pub fn find_picker_bindings(
+    guild: &Guild,
+    connection: &PgConnection,
+) -> Vec<(MessageId, Vec<ColourReaction>)> {
+    let pickers = PickerMessage::belonging_to(guild)
+        .order(p::id)
+        .load::<PickerMessage>(connection)
+        .unwrap_or_default();
+    let bindings = ColourReaction::belonging_to(&pickers)
+        .load::<ColourReaction>(connection)
+        .unwrap_or_default()
+        .grouped_by(&pickers);
+
+    pickers
+        .iter()
+        .zip(bindings)
+        .filter_map(|(picker, bindings)| picker.id.to_u64().map(|id| (MessageId(id), bindings)))
+        .collect()
+}
+
+/// Brings the guild's picker messages in line with the colours. Messages are edited where they are,
+/// only the reactions that changed are added or taken off, and each message is saved as soon as it's done,
+/// so a failure halfway through still leaves the finished messages tracked.
+pub fn sync_pickers(
+    channel: ChannelId,
+    guild: &Guild,
+    colours: &[Colour],
+    connection: &PgConnection,
+) -> Result<(), SerenityError> {
+    let existing = find_picker_bindings(guild, connection);
+    let database_error = |_| SerenityError::Other("Couldn't save the colour pickers.");
+
+    for (message, page) in plan_pickers(colours, &existing) {
+        let message = match message {
+            Some(message) if page.is_empty() => {
+                let _ = channel.delete_message(message);
+                remove_picker(message, connection).map_err(database_error)?;
+                continue;
+            }
+            Some(message) => match channel.message(message) {
+                Ok(message) => Some(message),
+                // deleted by hand, the page gets posted again.
+                Err(_) => {
+                    remove_picker(message, connection).map_err(database_error)?;
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let message = match message {
+            Some(message) => update_picker(channel, &message, &page)?,
+            None => post_picker(channel, &page)?,
+        };
+
+        save_picker(guild, message, &page, connection).map_err(database_error)?;
+    }
+
+    Ok(())
+}
+
+/// Posts a picker message and reacts with every bound emoji, so users only have to click them.
+fn post_picker(channel: ChannelId, page: &[(Colour, &str)]) -> Result<MessageId, SerenityError> {
+    let message = channel.say(&generate_picker_message(page))?;
+
+    for &(_, emoji) in page {
+        message.react(emoji.to_string())?;
+    }
+
+    Ok(message.id)
+}
+
+/// Edits a picker message to match the page, leaving the reactions that are still bound alone.
+fn update_picker(
+    channel: ChannelId,
+    message: &Message,
+    page: &[(Colour, &str)],
+) -> Result<MessageId, SerenityError> {
+    let content = generate_picker_message(page);
+
+    if message.content != content {
+        channel.edit_message(message.id, |m| m.content(&content))?;
+    }
+
+    let own_reactions = message
+        .reactions
+        .iter()
+        .filter(|reaction| reaction.me)
+        .map(|reaction| &reaction.reaction_type)
+        .collect::<Vec<_>>();
+
+    for reaction in &own_reactions {
+        if !page.iter().any(|&(_, emoji)| emoji == emoji_key(reaction)) {
+            channel.delete_reaction(message.id, None, (*reaction).clone())?;
+        }
+    }
+
+    for &(_, emoji) in page {
+        if !own_reactions.iter().any(|reaction| emoji_key(reaction) == emoji) {
+            message.react(emoji.to_string())?;
+        }
+    }
+
+    Ok(message.id)
+}
+
+/// Saves a single picker message and its emoji bindings, replacing whatever was saved for it before.
+pub fn save_picker(
+    guild: &Guild,
+    message: MessageId,
+    page: &[(Colour, &str)],
+    connection: &PgConnection,
+) -> QueryResult<()> {
+    let id = BigDecimal::from_u64(message.0).ok_or(diesel::result::Error::NotFound)?;
+    let colour_ids = page
+        .iter()
+        .map(|&(ref colour, _)| colour.id.clone())
+        .collect::<Vec<_>>();
+
+    connection.transaction(|| {
+        diesel::delete(reactions_table.filter(
+            r::message_id
+                .eq(&id)
+                .or(r::colour_id.eq(diesel::dsl::any(colour_ids))),
+        )).execute(connection)?;
+
+        diesel::insert_into(pickers_table)
+            .values(&PickerMessage {
+                id: id.clone(),
+                guild_id: guild.id.clone(),
+            }).on_conflict_do_nothing()
+            .execute(connection)?;
+
+        let bindings = page
+            .iter()
+            .map(|&(ref colour, emoji)| ColourReaction {
+                colour_id: colour.id.clone(),
+                message_id: id.clone(),
+                emoji: emoji.to_string(),
+            }).collect::<Vec<_>>();
+
+        diesel::insert_into(reactions_table)
+            .values(&bindings)
+            .execute(connection)?;
+
+        Ok(())
+    })
+}
+
+/// Forgets a picker message, its bindings go with it.
+fn remove_picker(message: MessageId, connection: &PgConnection) -> QueryResult<usize> {
+    let id = BigDecimal::from_u64(message.0).ok_or(diesel::result::Error::NotFound)?;
+
+    diesel::delete(pickers_table.find(id)).execute(connection)
+}
+
+/// Replaces the guild's picker messages and emoji bindings, clearing them out when given none.
+pub fn save_pickers(
+    guild: &Guild,
+    pickers: &[(MessageId, PickerPage)],
+    connection: &PgConnection,
+) -> QueryResult<()> {
+    connection.transaction(|| {
+        // the bindings are deleted along with their message.
+        diesel::delete(pickers_table.filter(p::guild_id.eq(&guild.id))).execute(connection)?;
+
+        for &(message, ref page) in pickers {
+            let id = BigDecimal::from_u64(message.0).ok_or(diesel::result::Error::NotFound)?;
+
+            diesel::insert_into(pickers_table)
+                .values(&PickerMessage {
+                    id: id.clone(),
+                    guild_id: guild.id.clone(),
+                }).execute(connection)?;
+
+            let bindings = page
+                .iter()
+                .map(|&(ref colour, emoji)| ColourReaction {
+                    colour_id: colour.id.clone(),
+                    message_id: id.clone(),
+                    emoji: emoji.to_string(),
+                }).collect::<Vec<_>>();
+
+            diesel::insert_into(reactions_table)
+                .values(&bindings)
+                .execute(connection)?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Gets the ids of the guild's picker messages, so they can be cleared out on a refresh.
+pub fn find_picker_messages(guild: &Guild, connection: &PgConnection) -> Vec<MessageId> {
+    PickerMessage::belonging_to(guild)
+        .load::<PickerMessage>(connection)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|picker| picker.id.to_u64())
+        .map(MessageId)
+        .collect()
+}
+
+/// Finds the colour bound to an emoji on a picker message.
+pub fn find_colour_for_reaction(
+    message: MessageId,
+    emoji: &str,
+    connection: &PgConnection,
+) -> Option<Colour> {
+    let id = BigDecimal::from_u64(message.0)?;
+
+    reactions_table
+        .inner_join(colours_table)
+        .filter(r::message_id.eq(id))
+        .filter(r::emoji.eq(emoji))
+        .select(colours::all_columns)
+        .first::<Colour>(connection)
+        .ok()
+}
+
+/// Finds where a colour sits on the picker messages.
+pub fn find_binding_for_colour(role: RoleId, connection: &PgConnection) -> Option<ColourReaction> {
+    let id = BigDecimal::from_u64(role.0)?;
+
+    reactions_table.find(id).first::<ColourReaction>(connection).ok()
+}
+
+fn guild_for_colour(colour: &Colour) -> Option<GuildId> {
+    colour.guild_id.to_u64().map(GuildId)
+}
+
+/// Gives the user the colour bound to the reaction, then takes away their reactions for any colours they had before.
+pub fn pick_colour_from_reaction(
+    reaction: &Reaction,
+    connection: &PgConnection,
+) -> Result<(), SerenityError> {
+    let colour =
+        match find_colour_for_reaction(reaction.message_id, &emoji_key(&reaction.emoji), connection)
+        {
+            Some(colour) => colour,
+            None => return Ok(()),
+        };
+
+    let guild = guild_for_colour(&colour)
+        .and_then(|id| id.find())
+        .ok_or(SerenityError::Model(ModelError::GuildNotFound))?;
+
+    let (role, old_roles) = {
+        let guild = guild.read();
+        let role = actions::colours::search_role(&colour, &guild)
+            .ok_or(SerenityError::Model(ModelError::ItemMissing))?;
+
+        let now = utils::unix_timestamp();
+
+        // locked colours just take the reaction back, the list already shows which ones are locked.
+        if actions::requirements::check_member(&colour, reaction.user_id, &guild, now, connection)
+            .is_err()
+        {
+            let _ = reaction.delete();
+            return Ok(());
+        }
+
+        let old_roles = match guild.members.get(&reaction.user_id) {
+            Some(member) => {
+                actions::colours::get_managed_roles_from_user(member, guild.id, connection)?
+            }
+            None => vec![],
+        };
+
+        (role, old_roles)
+    };
+
+    let user = reaction.user()?;
+    actions::colours::assign_colour_to_user(&user, guild.write(), &role, connection)?;
+
+    for old_role in old_roles.into_iter().filter(|id| *id != role.id) {
+        let binding = match find_binding_for_colour(old_role, connection) {
+            Some(binding) => binding,
+            None => continue,
+        };
+
+        if let Some(message) = binding.message_id.to_u64() {
+            let emoji = ReactionType::Unicode(binding.emoji);
+            let _ = reaction
+                .channel_id
+                .delete_reaction(MessageId(message), Some(user.id), emoji);
+        }
+    }
+
+    Ok(())
+}
+
+/// Takes the colour bound to the reaction away from the user, if they still have it.
+pub fn clear_colour_from_reaction(
+    reaction: &Reaction,
+    connection: &PgConnection,
+) -> Result<(), SerenityError> {
+    let colour =
+        match find_colour_for_reaction(reaction.message_id, &emoji_key(&reaction.emoji), connection)
+        {
+            Some(colour) => colour,
+            None => return Ok(()),
+        };
+
+    let guild = guild_for_colour(&colour)
+        .and_then(|id| id.find())
+        .ok_or(SerenityError::Model(ModelError::GuildNotFound))?;
+
+    let role = colour
+        .id
+        .to_u64()
+        .map(RoleId)
+        .ok_or(SerenityError::Model(ModelError::ItemMissing))?;
+
+    let mut guild = guild.write();
+
+    match guild.members.get_mut(&reaction.user_id) {
+        Some(member) if member.roles.contains(&role) => member.remove_role(role),
+        _ => Ok(()),
+    }
+}
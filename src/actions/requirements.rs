@@ -0,0 +1,222 @@
+use failure::Error;
+
+use diesel;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+
+use db::models::{Colour, ColourRequirement, Guild, GuildSettings};
+
+use db::schema::colour_requirements::dsl as q;
+use db::schema::colour_requirements::table as requirements_table;
+use db::schema::colours::dsl as c;
+use db::schema::colours::table as colours_table;
+use db::schema::guilds::table as guilds_table;
+
+use serenity::model::guild::{Guild as DiscordGuild, Member as DiscordMember};
+use serenity::model::id::{RoleId, UserId};
+
+use bigdecimal::BigDecimal;
+use num_traits::cast::ToPrimitive;
+
+/// Discord hands boosters a managed role, it's found by name since it has no other marker to go by.
+/// Guilds that renamed it set the `booster_role` setting instead.
+pub const BOOSTER_ROLE_NAMES: [&str; 2] = ["Nitro Booster", "Server Booster"];
+
+/// A single thing a member needs before they can pick a colour.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Requirement {
+    /// Any one of the roles.
+    Roles(Vec<RoleId>),
+    Booster,
+    DaysInServer(u32),
+}
+
+/// Gets the requirements for a single colour, `None` if it's open to everyone.
+pub fn find_for_colour(colour: &Colour, connection: &PgConnection) -> Option<ColourRequirement> {
+    ColourRequirement::belonging_to(colour)
+        .first::<ColourRequirement>(connection)
+        .ok()
+}
+
+/// Gets the requirements for every colour in the guild that has them.
+pub fn find_for_guild(guild: &Guild, connection: &PgConnection) -> Vec<ColourRequirement> {
+    requirements_table
+        .inner_join(colours_table)
+        .filter(c::guild_id.eq(&guild.id))
+        .select((q::colour_id, q::role_ids, q::boosters_only, q::min_days))
+        .load::<ColourRequirement>(connection)
+        .unwrap_or_default()
+}
+
+/// Saves the requirements for a colour, replacing the old ones. Empty requirements are removed instead.
+pub fn save_requirement(
+    requirement: &ColourRequirement,
+    connection: &PgConnection,
+) -> QueryResult<ColourRequirement> {
+    connection.transaction(|| {
+        diesel::delete(requirements_table.find(&requirement.colour_id)).execute(connection)?;
+
+        if requirement.is_empty() {
+            return Ok(requirement.clone());
+        }
+
+        diesel::insert_into(requirements_table)
+            .values(requirement)
+            .get_result::<ColourRequirement>(connection)
+    })
+}
+
+/// The role discord gives to members boosting the guild, if it has one.
+/// The `booster_role` setting wins over the name, unless that role was deleted.
+pub fn find_booster_role(guild: &DiscordGuild, settings: &GuildSettings) -> Option<RoleId> {
+    settings
+        .booster_role
+        .map(RoleId)
+        .filter(|role| guild.roles.contains_key(role))
+        .or_else(|| {
+            guild
+                .roles
+                .values()
+                .find(|role| role.managed && BOOSTER_ROLE_NAMES.contains(&role.name.as_str()))
+                .map(|role| role.id)
+        })
+}
+
+/// Every requirement that applies, met or not.
+pub fn list_requirements(requirement: &ColourRequirement) -> Vec<Requirement> {
+    let mut requirements = vec![];
+
+    let roles = requirement
+        .role_ids
+        .iter()
+        .filter_map(|id| id.to_u64())
+        .map(RoleId)
+        .collect::<Vec<_>>();
+
+    if !roles.is_empty() {
+        requirements.push(Requirement::Roles(roles));
+    }
+
+    if requirement.boosters_only {
+        requirements.push(Requirement::Booster);
+    }
+
+    if requirement.min_days > 0 {
+        requirements.push(Requirement::DaysInServer(requirement.min_days as u32));
+    }
+
+    requirements
+}
+
+/// Checks the requirements against what a member has, returning the ones they're missing.
+/// `joined_at` and `now` are unix timestamps.
+pub fn unmet_requirements(
+    requirement: &ColourRequirement,
+    roles: &[RoleId],
+    booster_role: Option<RoleId>,
+    joined_at: Option<i64>,
+    now: i64,
+) -> Vec<Requirement> {
+    let is_booster = booster_role.map(|role| roles.contains(&role)).unwrap_or(false);
+    let days_in_server = joined_at
+        .map(|joined| ((now - joined).max(0) / (24 * 60 * 60)) as u32)
+        .unwrap_or(0);
+
+    list_requirements(requirement)
+        .into_iter()
+        .filter(|needed| match *needed {
+            Requirement::Roles(ref needed) => !needed.iter().any(|role| roles.contains(role)),
+            Requirement::Booster => !is_booster,
+            Requirement::DaysInServer(days) => days_in_server < days,
+        }).collect()
+}
+
+/// Checks the requirements against a member of the guild.
+pub fn unmet_requirements_for_member(
+    requirement: &ColourRequirement,
+    member: &DiscordMember,
+    guild: &DiscordGuild,
+    settings: &GuildSettings,
+    now: i64,
+) -> Vec<Requirement> {
+    unmet_requirements(
+        requirement,
+        &member.roles,
+        find_booster_role(guild, settings),
+        member.joined_at.map(|joined| joined.timestamp()),
+        now,
+    )
+}
+
+/// Writes the requirements out as part of a sentence, like "the **Supporter** role and 7 days in the server".
+pub fn describe_requirements(requirements: &[Requirement], guild: &DiscordGuild) -> String {
+    let parts = requirements
+        .iter()
+        .map(|requirement| match *requirement {
+            Requirement::Roles(ref roles) => {
+                let names = roles
+                    .iter()
+                    .map(|role| match guild.roles.get(role) {
+                        Some(role) => format!("**{}**", role.name),
+                        None => "a deleted".to_string(),
+                    }).collect::<Vec<_>>()
+                    .join(" or ");
+
+                format!("the {} role", names)
+            }
+            Requirement::Booster => "boosting the server".to_string(),
+            Requirement::DaysInServer(1) => "a day in the server".to_string(),
+            Requirement::DaysInServer(days) => format!("{} days in the server", days),
+        }).collect::<Vec<_>>();
+
+    match parts.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        Some((last, _)) => last.clone(),
+        None => "nothing".to_string(),
+    }
+}
+
+/// Makes sure the user meets the colour's requirements, the error says what they're missing.
+pub fn check_member(
+    colour: &Colour,
+    user: UserId,
+    guild: &DiscordGuild,
+    now: i64,
+    connection: &PgConnection,
+) -> Result<(), Error> {
+    let requirement = match find_for_colour(colour, connection) {
+        Some(requirement) => requirement,
+        None => return Ok(()),
+    };
+
+    let member = guild
+        .members
+        .get(&user)
+        .ok_or_else(|| format_err!("Couldn't find you in the server."))?;
+
+    let settings = guilds_table
+        .find(&colour.guild_id)
+        .first::<Guild>(connection)
+        .map(|record| record.settings())
+        .unwrap_or_default();
+
+    let unmet = unmet_requirements_for_member(&requirement, member, guild, &settings, now);
+
+    if unmet.is_empty() {
+        Ok(())
+    } else {
+        Err(format_err!(
+            "`{}` is locked, it needs {}.",
+            colour.name,
+            describe_requirements(&unmet, guild)
+        ))
+    }
+}
+
+/// The ids of the colours in the guild that have requirements, for marking them in the list.
+pub fn find_locked_ids(guild: &Guild, connection: &PgConnection) -> Vec<BigDecimal> {
+    find_for_guild(guild, connection)
+        .into_iter()
+        .map(|requirement| requirement.colour_id)
+        .collect()
+}
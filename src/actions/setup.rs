@@ -0,0 +1,192 @@
+use actions;
+
+use db::models::{Colour, Guild};
+
+use diesel::pg::PgConnection;
+
+use failure::Error;
+
+use serenity::model::guild::Guild as DiscordGuild;
+use serenity::model::id::{ChannelId, RoleId, UserId};
+use serenity::model::permissions::Permissions;
+use serenity::Error as SerenityError;
+
+use bigdecimal::BigDecimal;
+use num_traits::{FromPrimitive, ToPrimitive};
+
+use std::fmt::{Display, Error as FmtError, Formatter};
+
+/// Permissions the bot needs to manage colours and the colour channel, with a readable name for each.
+pub const REQUIRED_PERMISSIONS: [(Permissions, &str); 4] = [
+    (Permissions::MANAGE_ROLES, "Manage Roles"),
+    (Permissions::MANAGE_MESSAGES, "Manage Messages"),
+    (Permissions::ATTACH_FILES, "Attach Files"),
+    (Permissions::ADD_REACTIONS, "Add Reactions"),
+];
+
+/// Something that stops the bot from working properly in a guild.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SetupProblem {
+    /// The bot is missing a server wide permission.
+    MissingPermission(&'static str),
+    /// The bot is missing a permission in the colour channel.
+    MissingChannelPermission(&'static str),
+    /// Colour roles at or above the bot's highest role, which the bot can't give out.
+    RolesAboveBot(Vec<String>),
+    NoColourChannel,
+}
+
+impl Display for SetupProblem {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
+        match *self {
+            SetupProblem::MissingPermission(name) => {
+                write!(fmt, "I'm missing the **{}** permission.", name)
+            }
+            SetupProblem::MissingChannelPermission(name) => write!(
+                fmt,
+                "I'm missing the **{}** permission in the colour channel.",
+                name
+            ),
+            SetupProblem::RolesAboveBot(ref roles) => write!(
+                fmt,
+                "My highest role needs to be above the colour roles, move it above: {}",
+                roles.join(", ")
+            ),
+            SetupProblem::NoColourChannel => write!(fmt, "No colour channel has been set."),
+        }
+    }
+}
+
+/// The highest position out of the roles the bot has.
+pub fn highest_role_position(guild: &DiscordGuild, self_id: UserId) -> i64 {
+    guild
+        .members
+        .get(&self_id)
+        .map(|member| {
+            member
+                .roles
+                .iter()
+                .filter_map(|id| guild.roles.get(id))
+                .map(|role| role.position)
+                .max()
+                .unwrap_or(0)
+        }).unwrap_or(0)
+}
+
+/// Checks the bot's permissions and role position in the guild, returning everything that needs fixing.
+pub fn check_guild(
+    guild: &DiscordGuild,
+    self_id: UserId,
+    colours: &[Colour],
+    colour_channel: Option<ChannelId>,
+) -> Vec<SetupProblem> {
+    let permissions = guild.member_permissions(self_id);
+
+    let mut problems = REQUIRED_PERMISSIONS
+        .iter()
+        .filter(|&&(permission, _)| !permissions.contains(permission))
+        .map(|&(_, name)| SetupProblem::MissingPermission(name))
+        .collect::<Vec<_>>();
+
+    match colour_channel {
+        Some(channel) => {
+            let channel_permissions = guild.permissions_in(channel, self_id);
+
+            // server wide problems are already listed, only repeat the ones the channel takes away.
+            problems.extend(
+                REQUIRED_PERMISSIONS
+                    .iter()
+                    .filter(|&&(permission, _)| {
+                        permissions.contains(permission)
+                            && !channel_permissions.contains(permission)
+                    }).map(|&(_, name)| SetupProblem::MissingChannelPermission(name)),
+            );
+        }
+        None => problems.push(SetupProblem::NoColourChannel),
+    }
+
+    let top_position = highest_role_position(guild, self_id);
+
+    let roles_above = colours
+        .iter()
+        .filter_map(|colour| colour.id.to_u64())
+        .filter_map(|id| guild.roles.get(&RoleId(id)))
+        .filter(|role| role.position >= top_position)
+        .map(|role| role.name.clone())
+        .collect::<Vec<_>>();
+
+    if !roles_above.is_empty() {
+        problems.push(SetupProblem::RolesAboveBot(roles_above));
+    }
+
+    problems
+}
+
+/// Builds the setup checklist sent when joining a guild and by the doctor command.
+pub fn generate_checklist(guild_name: &str, problems: &[SetupProblem], prefix: &str) -> String {
+    if problems.is_empty() {
+        return format!(
+            "Everything is set up correctly in **{}**. Add colours with `{} add`.",
+            guild_name, prefix
+        );
+    }
+
+    let steps = problems
+        .iter()
+        .map(|problem| match *problem {
+            SetupProblem::NoColourChannel => format!(
+                "- [ ] {} Use `{} setchannel` in the channel the colour list should go in.",
+                problem, prefix
+            ),
+            _ => format!("- [ ] {}", problem),
+        }).collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        "A few things need fixing in **{}** before colours will work:\n\n{}\n\nRun `{} doctor` to check again once they're done.",
+        guild_name, steps, prefix
+    )
+}
+
+/// Tells the admins the colour channel is gone and how to set a new one.
+pub fn generate_channel_deleted_message(guild_name: &str, prefix: &str) -> String {
+    format!(
+        "The colour channel in **{}** was deleted, so the colour list can't be posted anymore. Use `{} setchannel` in another channel to set it up again.",
+        guild_name, prefix
+    )
+}
+
+/// Gets or creates the guild's record, then checks the guild against its colours and colour channel.
+pub fn check_guild_with_record(
+    guild: &DiscordGuild,
+    self_id: UserId,
+    connection: &PgConnection,
+) -> Result<(Guild, Vec<SetupProblem>), Error> {
+    let id = BigDecimal::from_u64(guild.id.0).ok_or(::diesel::result::Error::NotFound)?;
+    let record = actions::guilds::check_or_create_guild(&id, connection).into_result()?;
+
+    let colours = actions::colours::find_all(&record, connection).unwrap_or_default();
+    let channel = record
+        .channel_id
+        .as_ref()
+        .and_then(|id| id.to_u64())
+        .map(ChannelId);
+
+    let problems = check_guild(guild, self_id, &colours, channel);
+
+    Ok((record, problems))
+}
+
+/// DMs the guild owner, falling back to the system channel if their DMs are closed.
+pub fn notify_admins(guild: &DiscordGuild, message: &str) -> Result<(), SerenityError> {
+    let dm = guild
+        .owner_id
+        .create_dm_channel()
+        .and_then(|channel| channel.say(message));
+
+    match (dm, guild.system_channel_id) {
+        (Ok(_), _) => Ok(()),
+        (Err(_), Some(channel)) => channel.say(message).map(|_| ()),
+        (Err(e), None) => Err(e),
+    }
+}
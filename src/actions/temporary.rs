@@ -0,0 +1,233 @@
+use failure::Error;
+
+use actions;
+use utils::{format_interval, parse_interval};
+
+use diesel;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+
+use constants::temporary::{MAX_TEMPORARY_DURATION, MIN_TEMPORARY_DURATION};
+use db::models::{Colour, Guild, TemporaryColour};
+
+use db::schema::temporary_colours::dsl as t;
+use db::schema::temporary_colours::table as temporary_table;
+
+use serenity::model::guild::{Guild as DiscordGuild, Role as DiscordRole};
+use serenity::model::id::{GuildId, RoleId, UserId};
+use serenity::model::user::User as DiscordUser;
+use serenity::prelude::ModelError;
+use serenity::Error as SerenityError;
+
+use bigdecimal::BigDecimal;
+use num_traits::cast::{FromPrimitive, ToPrimitive};
+use parking_lot::RwLockWriteGuard;
+
+/// Splits a duration off the end of a colour name, so `gold for 24h` is `("gold", Some(86400))`.
+/// Names without a readable duration are left as they are.
+pub fn split_duration(input: &str) -> (String, Option<u64>) {
+    let words = input.split_whitespace().collect::<Vec<_>>();
+
+    // the duration is either one word like `24h`, or two like `24 hours`.
+    for length in 1..3 {
+        if words.len() < length + 2 {
+            break;
+        }
+
+        let split = words.len() - length;
+
+        if !words[split - 1].eq_ignore_ascii_case("for") {
+            continue;
+        }
+
+        if let Some(duration) = parse_interval(&words[split..].join(" ")) {
+            return (words[..split - 1].join(" "), Some(duration));
+        }
+    }
+
+    (words.join(" "), None)
+}
+
+/// Makes sure a duration is in the range temporary colours can be given for.
+pub fn check_duration(duration: u64) -> Result<u64, Error> {
+    if duration < MIN_TEMPORARY_DURATION || duration > MAX_TEMPORARY_DURATION {
+        Err(format_err!(
+            "Temporary colours can last between {} and {}.",
+            format_interval(MIN_TEMPORARY_DURATION),
+            format_interval(MAX_TEMPORARY_DURATION)
+        ))
+    } else {
+        Ok(duration)
+    }
+}
+
+/// Gets the temporary colour a member has in the guild.
+pub fn find_for_user(guild: &Guild, user: UserId, connection: &PgConnection) -> Option<TemporaryColour> {
+    let user = BigDecimal::from_u64(user.0)?;
+
+    temporary_table
+        .find((&guild.id, user))
+        .first::<TemporaryColour>(connection)
+        .ok()
+}
+
+/// Saves a temporary colour for a member, replacing the one they had.
+pub fn save_temporary_colour(
+    guild: &Guild,
+    user: UserId,
+    colour: &Colour,
+    previous: &[RoleId],
+    expires_at: i64,
+    connection: &PgConnection,
+) -> QueryResult<TemporaryColour> {
+    let record = TemporaryColour {
+        guild_id: guild.id.clone(),
+        user_id: BigDecimal::from_u64(user.0).ok_or(diesel::result::Error::NotFound)?,
+        colour_id: colour.id.clone(),
+        previous_colour_ids: previous
+            .iter()
+            .filter_map(|role| BigDecimal::from_u64(role.0))
+            .collect(),
+        expires_at,
+    };
+
+    connection.transaction(|| {
+        cancel_for_user(guild, user, connection)?;
+
+        diesel::insert_into(temporary_table)
+            .values(&record)
+            .get_result::<TemporaryColour>(connection)
+    })
+}
+
+/// Forgets a member's temporary colour, so the colour they have now stays on.
+pub fn cancel_for_user(guild: &Guild, user: UserId, connection: &PgConnection) -> QueryResult<usize> {
+    let user = BigDecimal::from_u64(user.0).ok_or(diesel::result::Error::NotFound)?;
+
+    diesel::delete(temporary_table.find((&guild.id, user))).execute(connection)
+}
+
+/// Gets the temporary colours that ran out before `now`.
+pub fn find_expired(now: i64, connection: &PgConnection) -> QueryResult<Vec<TemporaryColour>> {
+    temporary_table
+        .filter(t::expires_at.le(now))
+        .order(t::expires_at)
+        .load::<TemporaryColour>(connection)
+}
+
+/// Gives the user a colour until `expires_at`, remembering the colours they had so they can be given back.
+/// Stacking temporary colours keeps the colours from before the first one.
+pub fn give_temporary_colour(
+    author: &DiscordUser,
+    discord_guild: RwLockWriteGuard<DiscordGuild>,
+    colour: &Colour,
+    colour_role: &DiscordRole,
+    expires_at: i64,
+    connection: &PgConnection,
+) -> Result<(), SerenityError> {
+    let guild_record = actions::guilds::convert_guild_to_record(discord_guild.id, connection)
+        .ok_or(SerenityError::Model(ModelError::GuildNotFound))?;
+
+    let previous = {
+        let member = discord_guild
+            .members
+            .get(&author.id)
+            .ok_or(SerenityError::Model(ModelError::InvalidUser))?;
+
+        let still_temporary = |existing: &TemporaryColour| {
+            existing
+                .colour_id
+                .to_u64()
+                .map(|id| member.roles.contains(&RoleId(id)))
+                .unwrap_or(false)
+        };
+
+        let previous = match find_for_user(&guild_record, author.id, connection) {
+            Some(ref existing) if still_temporary(existing) => existing
+                .previous_colour_ids
+                .iter()
+                .filter_map(|id| id.to_u64())
+                .map(RoleId)
+                .collect(),
+            _ => {
+                actions::colours::get_managed_roles_from_user(member, discord_guild.id, connection)?
+            }
+        };
+
+        previous
+            .into_iter()
+            .filter(|id| *id != colour_role.id)
+            .collect::<Vec<_>>()
+    };
+
+    save_temporary_colour(
+        &guild_record,
+        author.id,
+        colour,
+        &previous,
+        expires_at,
+        connection,
+    ).map_err(|_| SerenityError::Other("Couldn't access the database."))?;
+
+    actions::colours::assign_colour_to_user(author, discord_guild, colour_role, connection)
+}
+
+/// Takes an expired colour off the member and gives back the colours they had before.
+/// Members who picked another colour in the meantime are left alone.
+pub fn expire_colour(temporary: &TemporaryColour) -> Result<(), SerenityError> {
+    let guild = temporary
+        .guild_id
+        .to_u64()
+        .and_then(|id| GuildId(id).find())
+        .ok_or(SerenityError::Model(ModelError::GuildNotFound))?;
+
+    let (user, colour) = match (temporary.user_id.to_u64(), temporary.colour_id.to_u64()) {
+        (Some(user), Some(colour)) => (UserId(user), RoleId(colour)),
+        _ => return Ok(()),
+    };
+
+    let mut guild = guild.write();
+
+    let previous = temporary
+        .previous_colour_ids
+        .iter()
+        .filter_map(|id| id.to_u64())
+        .map(RoleId)
+        .filter(|id| guild.roles.contains_key(id))
+        .collect::<Vec<_>>();
+
+    let member = match guild.members.get_mut(&user) {
+        Some(member) => member,
+        None => return Ok(()),
+    };
+
+    if !member.roles.contains(&colour) {
+        return Ok(());
+    }
+
+    member.remove_role(colour)?;
+
+    if previous.is_empty() {
+        Ok(())
+    } else {
+        member.add_roles(&previous)
+    }
+}
+
+/// Takes off every temporary colour that ran out. Returns the amount of colours that expired.
+pub fn expire_colours(now: i64, connection: &PgConnection) -> QueryResult<usize> {
+    let mut expired = 0;
+
+    for temporary in find_expired(now, connection)? {
+        // the guild isn't cached yet right after a restart, or discord refused the change.
+        // either way the colour is still on, so try again on the next check.
+        if expire_colour(&temporary).is_err() {
+            continue;
+        }
+
+        diesel::delete(&temporary).execute(connection)?;
+        expired += 1;
+    }
+
+    Ok(expired)
+}
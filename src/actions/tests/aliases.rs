@@ -0,0 +1,36 @@
+use actions::aliases::*;
+use actions::colours::{find_from_name, find_from_role_id};
+use actions::tests::*;
+use utils;
+
+#[test]
+fn colours_can_be_found_by_alias() {
+    do_test_transaction!(|conn| {
+        let red = find_from_role_id(RED_COLOUR_ID, conn).expect(RECORD_MISSING_FAILURE);
+
+        assert!(find_from_name("Scarlet", &DB_GUILD, conn).is_none());
+
+        add_alias(&red, "Scarlet", &DB_GUILD, conn).expect("Error adding the alias");
+
+        let found = find_from_name("scarlet", &DB_GUILD, conn).expect(RECORD_MISSING_FAILURE);
+        assert_eq!(found.name, "Red");
+        assert_eq!(find_for_colour(&red, conn), vec!["Scarlet"]);
+
+        let removed = remove_alias(&red, "SCARLET", conn).expect("Error removing the alias");
+        assert_eq!(removed, "Scarlet");
+        assert!(find_for_colour(&red, conn).is_empty());
+    })
+}
+
+#[test]
+fn aliases_can_not_clash_with_other_names() {
+    do_test_transaction!(|conn| {
+        let red = find_from_role_id(RED_COLOUR_ID, conn).expect(RECORD_MISSING_FAILURE);
+        let green = find_from_role_id(GREEN_COLOUR_ID, conn).expect(RECORD_MISSING_FAILURE);
+
+        assert!(add_alias(&red, "green", &DB_GUILD, conn).is_err());
+
+        add_alias(&red, "Crimson", &DB_GUILD, conn).expect("Error adding the alias");
+        assert!(add_alias(&green, "crimson", &DB_GUILD, conn).is_err());
+    })
+}
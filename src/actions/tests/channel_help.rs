@@ -18,7 +18,7 @@ To get the colour named French Lilac, type:
 fn help_message_forms_correctly() {
     let names = vec!["Shadow Green".to_string(), "French Lilac".to_string()];
 
-    let full_message = generate_help_message(&names);
+    let full_message = generate_help_message(&names, "!c");
 
     assert_eq!(
         format!(
@@ -26,7 +26,8 @@ fn help_message_forms_correctly() {
 {}
 
 {}",
-            HELP_MESSAGE_FOR_CHANNEL, EXAMPLE_MESSAGE
+            HELP_MESSAGE_FOR_CHANNEL.replace("{prefix}", "!c"),
+            EXAMPLE_MESSAGE
         ),
         full_message
     );
@@ -36,8 +37,16 @@ fn help_message_forms_correctly() {
 fn message_should_not_fail_on_empty_lists() {
     let names: Vec<String> = vec![];
 
-    let full_message = generate_help_message(&names);
+    let full_message = generate_help_message(&names, "!c");
 
     println!("{}", full_message);
     assert!(full_message.contains("no colours"))
 }
+
+#[test]
+fn help_message_uses_the_given_prefix() {
+    let full_message = generate_help_message(&[], "?colour");
+
+    assert!(full_message.contains("?colour clear"));
+    assert!(!full_message.contains("{prefix}"));
+}
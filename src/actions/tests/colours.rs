@@ -2,22 +2,31 @@ use actions::colours::*;
 use actions::tests::*;
 use bigdecimal::ToPrimitive;
 use bigdecimal::{BigDecimal, FromPrimitive};
+use colours::images::{
+    ColourListBuilder, ColourListType, ListColour, Name, OutputFormat, DEFAULT_FONT_FAMILY,
+};
 use colours::models::ParsedColour;
 use db::models::Colour;
+use db::settings::MAX_FONT_SIZE;
 use diesel::PgConnection;
 use parking_lot::RwLock;
 use serenity::{
     http,
-    model::{guild::Member, id::UserId, permissions::Permissions},
+    model::{
+        guild::Member,
+        id::{GuildId, UserId},
+        permissions::Permissions,
+    },
+    utils::Colour as DiscordColour,
 };
-use std::{fs, sync::Arc};
+use std::sync::Arc;
 use utils;
 use CONFIG;
 
 fn get_colour_from_premade_list(name: &str, conn: &PgConnection) -> Option<Colour> {
     let alls = find_all(&DB_GUILD, conn).expect("Error getting guild colours for mock guild!");
 
-    get_nearest_colour_for_name(name, &alls)
+    get_nearest_colour_for_name(name, &alls, DB_GUILD.settings().fuzzy_tolerance)
 }
 
 #[test]
@@ -47,6 +56,30 @@ fn can_find_colour_with_misspelled_name() {
     });
 }
 
+#[test]
+fn tied_names_are_all_matched() {
+    let colours = ["Bluet", "Blues", "Red"]
+        .iter()
+        .enumerate()
+        .map(|(i, name)| Colour {
+            id: BigDecimal::from(i as u64),
+            name: name.to_string(),
+            guild_id: DB_GUILD.id.clone(),
+        }).collect::<Vec<_>>();
+
+    let matches = get_closest_colours_for_name("Blue", &colours, 2)
+        .into_iter()
+        .map(|colour| colour.name)
+        .collect::<Vec<_>>();
+
+    assert_eq!(matches, vec!["Bluet", "Blues"]);
+
+    let best = get_nearest_colour_for_name("Blue", &colours, 2).expect(RECORD_MISSING_FAILURE);
+    assert_eq!(best.name, "Bluet");
+
+    assert!(get_closest_colours_for_name("Green", &colours, 2).is_empty());
+}
+
 #[test]
 fn can_not_find_a_colour_that_does_not_exist() {
     do_test_transaction!(|conn| {
@@ -169,6 +202,67 @@ fn can_remove_record_from_db() {
     });
 }
 
+#[test]
+fn deleted_roles_remove_their_colour() {
+    do_test_transaction!(|conn| {
+        // the same role id in another guild's event is left alone.
+        assert!(remove_deleted_role(GuildId(1), RED_COLOUR_ID, conn).is_none());
+
+        let removed = remove_deleted_role(MOCK_GUILD_DATA.id, RED_COLOUR_ID, conn)
+            .expect(RECORD_MISSING_FAILURE);
+
+        assert_eq!(removed.name, "Red");
+        assert!(find_from_role_id(RED_COLOUR_ID, conn).is_none());
+        assert!(remove_deleted_role(MOCK_GUILD_DATA.id, EXAMPLE_ROLE_ID, conn).is_none());
+    });
+}
+
+#[test]
+fn role_updates_sync_the_colour() {
+    do_test_transaction!(|conn| {
+        let old = MOCK_GUILD_DATA.roles[&RED_COLOUR_ID].clone();
+
+        let mut renamed = old.clone();
+        renamed.name = "Crimson".to_string();
+
+        let synced = sync_role_update(Some(&old), &renamed, conn)
+            .expect("Error syncing the role update.")
+            .expect("A renamed role should refresh the list.");
+
+        assert_eq!(synced.name, "Crimson");
+
+        let mut recoloured = renamed.clone();
+        recoloured.colour = DiscordColour::from_rgb(1, 2, 3);
+
+        assert!(sync_role_update(Some(&renamed), &recoloured, conn)
+            .unwrap()
+            .is_some());
+        assert!(sync_role_update(Some(&recoloured), &recoloured, conn)
+            .unwrap()
+            .is_none());
+    });
+}
+
+#[test]
+fn the_bots_own_role_edits_are_skipped() {
+    do_test_transaction!(|conn| {
+        let old = MOCK_GUILD_DATA.roles[&GREEN_COLOUR_ID].clone();
+
+        let mut recoloured = old.clone();
+        recoloured.colour = DiscordColour::from_rgb(1, 2, 3);
+
+        expect_role_edit(GREEN_COLOUR_ID, old.name.clone(), recoloured.colour.0);
+        assert!(sync_role_update(Some(&old), &recoloured, conn)
+            .unwrap()
+            .is_none());
+
+        // the edit is only skipped once, later changes are someone else's.
+        assert!(sync_role_update(Some(&old), &recoloured, conn)
+            .unwrap()
+            .is_some());
+    });
+}
+
 #[test]
 fn can_not_remove_non_existant_record() {
     do_test_transaction!(|conn| {
@@ -409,21 +503,126 @@ fn can_generate_colour_image() {
         let colours = find_all(&DB_GUILD, conn)
             .expect("failure while trying to get all the colours for a guild.");
 
-        let result = generate_colour_image(&colours, &MOCK_GUILD_DATA)
+        let result = generate_colour_images(&colours, &[], &MOCK_GUILD_DATA, &DB_GUILD.settings())
             .expect("Error while generating the colour list.");
 
-        let path_exists = fs::File::open(result.clone()).is_ok();
+        // every png starts with the same 8 byte signature.
+        let are_pngs = result
+            .iter()
+            .all(|image| image.data.starts_with(b"\x89PNG\r\n\x1a\n"));
 
-        // cleanup
-        let _ = fs::remove_file(result);
+        assert_eq!(result.len(), 1, "Two colours should fit on one page!");
+        assert!(are_pngs, "The rendered images should be PNGs!");
+        assert_eq!(result[0].name, format!("{}-1.png", MOCK_GUILD_DATA.id.0));
+    })
+}
 
-        assert!(
-            path_exists,
-            "File should be in the OS, instead got an error!"
-        );
+#[test]
+fn can_generate_swatch_grid_image() {
+    do_test_transaction!(|conn| {
+        let colours = find_all(&DB_GUILD, conn)
+            .expect("failure while trying to get all the colours for a guild.");
+
+        let mut settings = DB_GUILD.settings();
+        settings.list_style = ColourListType::SwatchGrid;
+
+        let result = generate_colour_images(&colours, &[], &MOCK_GUILD_DATA, &settings)
+            .expect("Error while generating the swatch grid.");
+
+        // every png starts with the same 8 byte signature.
+        let are_pngs = result
+            .iter()
+            .all(|image| image.data.starts_with(b"\x89PNG\r\n\x1a\n"));
+
+        assert!(are_pngs, "The rendered images should be PNGs!");
+    })
+}
+
+#[test]
+fn can_generate_colour_list_in_other_formats() {
+    do_test_transaction!(|conn| {
+        let colours = find_all(&DB_GUILD, conn)
+            .expect("failure while trying to get all the colours for a guild.");
+
+        let svg = generate_colour_images_as(
+            &colours,
+            &[],
+            &MOCK_GUILD_DATA,
+            &DB_GUILD.settings(),
+            OutputFormat::Svg,
+        ).expect("Error while generating the svg colour list.");
+
+        assert!(svg[0].name.ends_with(".svg"));
+        assert!(String::from_utf8_lossy(&svg[0].data).starts_with("<svg"));
+
+        let webp = generate_colour_images_as(
+            &colours,
+            &[],
+            &MOCK_GUILD_DATA,
+            &DB_GUILD.settings(),
+            OutputFormat::Webp,
+        ).expect("Error while generating the webp colour list.");
+
+        // webp files are a RIFF container with a WEBP tag.
+        assert!(webp[0].data.starts_with(b"RIFF"));
+        assert_eq!(&webp[0].data[8..12], b"WEBP");
     })
 }
 
+#[test]
+fn hex_codes_fit_at_the_largest_font_size() {
+    do_test_transaction!(|conn| {
+        let colours = find_all(&DB_GUILD, conn)
+            .expect("failure while trying to get all the colours for a guild.");
+
+        let mut settings = DB_GUILD.settings();
+        settings.font_size = MAX_FONT_SIZE;
+        settings.show_hex_codes = true;
+        settings.contrast_warnings = true;
+
+        let result = generate_colour_images(&colours, &[], &MOCK_GUILD_DATA, &settings)
+            .expect("Error while generating the colour list.");
+
+        assert!(result[0].data.starts_with(b"\x89PNG\r\n\x1a\n"));
+
+        let mut builder = ColourListBuilder::new();
+        builder
+            .set_font(DEFAULT_FONT_FAMILY, MAX_FONT_SIZE)
+            .set_show_hex_codes(true);
+
+        // long names are cut short instead of running into the hex code, the markers stay.
+        let max_length = builder.max_label_length();
+        let label = builder.list_label("A very long colour name indeed", false, &["dark", "light"]);
+
+        assert!(max_length > 0);
+        assert!(label.chars().count() <= max_length, "{} is too long", label);
+        assert!(label.ends_with("[!]"));
+        assert_eq!(builder.list_label("Red", false, &[]), "Red");
+    })
+}
+
+#[test]
+fn colour_list_is_split_into_pages() {
+    let colours = (0..12)
+        .map(|i| ListColour {
+            name: Name(format!("Colour {}", i)),
+            colour: DiscordColour::from_rgb(i * 20, 0, 0),
+            added: u64::from(i),
+            usage: 0,
+            locked: false,
+        }).collect::<Vec<_>>();
+
+    let mut builder = ColourListBuilder::new();
+    builder.set_page_size(5);
+
+    let pages = builder.paginate(&colours);
+
+    assert_eq!(
+        pages.iter().map(Vec::len).collect::<Vec<_>>(),
+        vec![5, 5, 2]
+    );
+}
+
 // keeps throwing an invalidpermissions error, all
 // other gateway related things except this one
 // fails.
@@ -513,3 +712,49 @@ fn can_update_a_role_and_the_record() {
         );
     })
 }
+
+#[test]
+fn colour_only_roles_can_be_adopted() {
+    do_test_transaction!(|conn| {
+        let existing = find_all(&DB_GUILD, conn).expect(RECORD_MISSING_FAILURE);
+
+        // red and green are on the list already, the bot's own role is managed and `@everyone` has no colour.
+        let adoptable = find_adoptable_roles(&MOCK_GUILD_DATA, BOT_USER_ID, &existing);
+        assert_eq!(adoptable.len(), 1);
+        assert_eq!(adoptable[0].role.id, EXAMPLE_ROLE_ID);
+        assert_eq!(adoptable[0].name, "Example Role");
+
+        let mut guild = MOCK_GUILD_DATA.clone();
+        {
+            let example = guild.roles.get_mut(&EXAMPLE_ROLE_ID).expect(RECORD_MISSING_FAILURE);
+            example.name = "red".to_string();
+        }
+
+        assert_eq!(find_adoptable_roles(&guild, BOT_USER_ID, &existing)[0].name, "red (2)");
+
+        // the bot can't hand out roles at or above its own.
+        guild
+            .roles
+            .get_mut(&EXAMPLE_ROLE_ID)
+            .expect(RECORD_MISSING_FAILURE)
+            .position = 4;
+
+        assert!(find_adoptable_roles(&guild, BOT_USER_ID, &existing).is_empty());
+        assert!(find_adoptable_roles(&MOCK_GUILD_DATA, UserId(1), &existing).is_empty());
+
+        guild.roles.get_mut(&EXAMPLE_ROLE_ID).expect(RECORD_MISSING_FAILURE).position = 1;
+
+        guild
+            .roles
+            .get_mut(&EXAMPLE_ROLE_ID)
+            .expect(RECORD_MISSING_FAILURE)
+            .permissions = Permissions::ADMINISTRATOR;
+
+        assert!(find_adoptable_roles(&guild, BOT_USER_ID, &existing).is_empty());
+
+        let adopted =
+            adopt_roles(&adoptable, MOCK_GUILD_DATA.id, conn).expect("Error adopting the roles");
+        assert_eq!(adopted[0].name, "Example Role");
+        assert!(find_from_role_id(EXAMPLE_ROLE_ID, conn).is_some());
+    })
+}
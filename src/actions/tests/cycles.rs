@@ -0,0 +1,84 @@
+use actions::colours::find_from_role_id;
+use actions::cycles::*;
+use actions::tests::*;
+use constants::cycles::{MAX_CYCLE_INTERVAL, MIN_CYCLE_INTERVAL};
+use serenity::model::id::UserId;
+use utils;
+
+const USER: UserId = UserId(482110165651554330);
+
+#[test]
+fn intervals_are_read_in_any_unit() {
+    assert_eq!(utils::parse_interval("90s"), Some(90));
+    assert_eq!(utils::parse_interval("10"), Some(10 * 60));
+    assert_eq!(utils::parse_interval("2 hours"), Some(2 * 60 * 60));
+    assert_eq!(utils::parse_interval("1D"), Some(24 * 60 * 60));
+    assert_eq!(utils::parse_interval("soon"), None);
+    assert_eq!(utils::parse_interval("5y"), None);
+
+    assert_eq!(utils::format_interval(90 * 60), "1h 30m");
+    assert_eq!(utils::format_interval(0), "0s");
+}
+
+#[test]
+fn cycles_replace_each_other_and_stop() {
+    do_test_transaction!(|conn| {
+        let red = find_from_role_id(RED_COLOUR_ID, conn).expect(RECORD_MISSING_FAILURE);
+        let green = find_from_role_id(GREEN_COLOUR_ID, conn).expect(RECORD_MISSING_FAILURE);
+        let target = CycleTarget::User(USER);
+
+        assert!(start_cycle(&DB_GUILD, target, &[red.clone(), green.clone()], 60, 0, conn).is_err());
+        assert!(start_cycle(&DB_GUILD, target, &[red.clone()], MIN_CYCLE_INTERVAL, 0, conn).is_err());
+        assert!(
+            start_cycle(&DB_GUILD, target, &[red.clone(), green.clone()], MAX_CYCLE_INTERVAL + 1, 0, conn)
+                .is_err()
+        );
+        assert!(start_cycle(&DB_GUILD, target, &[red.clone(), green.clone()], 1 << 63, 0, conn).is_err());
+
+        start_cycle(&DB_GUILD, target, &[red.clone(), green.clone()], MIN_CYCLE_INTERVAL, 0, conn)
+            .expect("Error starting the cycle");
+        let cycle = start_cycle(&DB_GUILD, target, &[green, red], MIN_CYCLE_INTERVAL, 0, conn)
+            .expect("Error restarting the cycle");
+
+        assert_eq!(find_for_guild(&DB_GUILD, conn), vec![cycle.clone()]);
+        assert_eq!(CycleTarget::of(&cycle), Some(target));
+        assert_eq!(find_cycle_colours(&cycle, conn)[0].name, "Green");
+
+        assert_eq!(stop_cycle(&DB_GUILD, target, conn).unwrap(), 1);
+        assert_eq!(stop_cycle(&DB_GUILD, target, conn).unwrap(), 0);
+    })
+}
+
+#[test]
+fn due_cycles_advance_and_wrap_around() {
+    do_test_transaction!(|conn| {
+        let red = find_from_role_id(RED_COLOUR_ID, conn).expect(RECORD_MISSING_FAILURE);
+        let green = find_from_role_id(GREEN_COLOUR_ID, conn).expect(RECORD_MISSING_FAILURE);
+        let interval = MIN_CYCLE_INTERVAL as i64;
+
+        let cycle = start_cycle(
+            &DB_GUILD,
+            CycleTarget::Role(EXAMPLE_ROLE_ID),
+            &[red.clone(), green],
+            MIN_CYCLE_INTERVAL,
+            100,
+            conn,
+        ).expect("Error starting the cycle");
+
+        assert!(find_due_cycles(99, conn).unwrap().is_empty());
+        assert_eq!(find_due_cycles(100, conn).unwrap().len(), 1);
+
+        let cycle = advance_cycle(&cycle, 2, 100, conn).expect("Error advancing the cycle");
+        assert_eq!((cycle.position, cycle.next_run), (1, 100 + interval));
+        assert!(find_due_cycles(100, conn).unwrap().is_empty());
+
+        let cycle = advance_cycle(&cycle, 2, 100 + interval, conn).expect("Error advancing the cycle");
+        assert_eq!(cycle.position, 0);
+
+        // deleted colours are left out of the rotation.
+        ::actions::colours::remove_record(&red, conn).expect("Error removing the colour");
+        let colours = find_cycle_colours(&cycle, conn);
+        assert_eq!(colours.len(), 1);
+        assert_eq!(colours[0].name, "Green");
+    })
+}
@@ -2,7 +2,11 @@ use actions::guilds::*;
 use actions::tests::*;
 use bigdecimal::{BigDecimal, FromPrimitive};
 use serenity::http;
-use serenity::model::{channel::ChannelType, id::GuildId, user::User};
+use serenity::model::{
+    channel::ChannelType,
+    id::{ChannelId, GuildId},
+    user::User,
+};
 use utils;
 use CONFIG;
 
@@ -188,3 +192,68 @@ fn can_update_channel_message_with_failure() {
         );
     })
 }
+
+#[test]
+fn can_update_guild_settings() {
+    do_test_transaction!(|conn| {
+        let guild = convert_guild_to_record(MOCK_GUILD_DATA.id, conn).expect(RECORD_MISSING_FAILURE);
+
+        let mut settings = guild.settings();
+        settings.fuzzy_tolerance = 5;
+
+        let result = update_settings(&guild, &settings, conn)
+            .expect("Error while saving the settings for the guild");
+
+        assert_eq!(result.settings().fuzzy_tolerance, 5);
+    })
+}
+
+#[test]
+fn can_match_the_longest_prefix() {
+    let prefixes = vec!["!c".to_string(), "!colour".to_string()];
+
+    assert_eq!(
+        match_prefix("!COLOUR get red", &prefixes),
+        Some("!COLOUR".to_string())
+    );
+    assert_eq!(match_prefix("!c get red", &prefixes), Some("!c".to_string()));
+    assert_eq!(match_prefix("red", &prefixes), None);
+}
+
+#[test]
+fn deleted_colour_channel_is_cleared() {
+    do_test_transaction!(|conn| {
+        let record = clear_deleted_channel(DEFAULT_GUILD_CHANNEL, conn)
+            .expect("Error clearing the channel")
+            .expect("The channel should have belonged to the test guild");
+
+        assert_eq!(record.id, DB_GUILD.id);
+        assert_eq!(record.channel_id, None);
+
+        let other = clear_deleted_channel(ChannelId(DEFAULT_GUILD_CHANNEL.0 + 1), conn)
+            .expect("Error clearing the channel");
+
+        assert!(other.is_none());
+    })
+}
+
+#[test]
+fn guilds_are_only_purged_after_the_grace_period() {
+    do_test_transaction!(|conn| {
+        let id = MOCK_GUILD_DATA.id;
+
+        schedule_purge(id, 100, conn).expect("Error scheduling the purge");
+
+        assert_eq!(purge_expired_guilds(99, conn), Ok(0));
+        assert!(convert_guild_to_record(id, conn).is_some());
+
+        cancel_purge(id, conn).expect("Error cancelling the purge");
+        assert_eq!(purge_expired_guilds(100, conn), Ok(0));
+
+        schedule_purge(id, 100, conn).expect("Error scheduling the purge");
+        assert_eq!(purge_expired_guilds(100, conn), Ok(1));
+
+        assert!(convert_guild_to_record(id, conn).is_none());
+        assert!(::actions::colours::find_from_role_id(RED_COLOUR_ID, conn).is_none());
+    })
+}
@@ -7,7 +7,7 @@ use db::models::Guild as DBGuild;
 use serde_json;
 use serenity::model::{
     guild::Guild,
-    id::{ChannelId, RoleId},
+    id::{ChannelId, RoleId, UserId},
 };
 
 lazy_static! {
@@ -18,6 +18,8 @@ lazy_static! {
         channel_id: Some(BigDecimal::from(482110165651554327 as u64)),
         settings: serde_json::Value::Null,
         legacy: Some(true),
+        list_message_ids: vec![],
+        purge_at: None,
     };
 }
 
@@ -25,6 +27,7 @@ pub static DEFAULT_GUILD_CHANNEL: ChannelId = ChannelId(482110165651554327);
 pub static EXAMPLE_ROLE_ID: RoleId = RoleId(484529706037805056);
 pub static RED_COLOUR_ID: RoleId = RoleId(483501321945612319);
 pub static GREEN_COLOUR_ID: RoleId = RoleId(483501363708297225);
+pub static BOT_USER_ID: UserId = UserId(328671801246744576);
 pub static TEST_TRANSACTION_FAILURE: &str = "Failure while attempting to create a test transaction";
 pub static RECORD_MISSING_FAILURE: &str = "Record was missing from the test database!";
 
@@ -76,9 +79,23 @@ macro_rules! update_cache {
     }};
 }
 
+#[cfg(test)]
+pub mod aliases;
 #[cfg(test)]
 pub mod channel_help;
 #[cfg(test)]
 pub mod colours;
 #[cfg(test)]
+pub mod cycles;
+#[cfg(test)]
 pub mod guilds;
+#[cfg(test)]
+pub mod palettes;
+#[cfg(test)]
+pub mod pickers;
+#[cfg(test)]
+pub mod requirements;
+#[cfg(test)]
+pub mod setup;
+#[cfg(test)]
+pub mod temporary;
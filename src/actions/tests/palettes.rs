@@ -0,0 +1,100 @@
+use actions::colours::find_all;
+use actions::palettes::*;
+use actions::tests::*;
+use utils;
+
+fn entry(name: &str, hex: &str) -> PaletteEntry {
+    PaletteEntry {
+        name: name.to_string(),
+        hex: hex.to_string(),
+        aliases: vec![],
+        role_name: None,
+    }
+}
+
+#[test]
+fn palettes_survive_a_round_trip() {
+    let entries = vec![
+        PaletteEntry {
+            aliases: vec!["scarlet".to_string(), "crimson".to_string()],
+            ..entry("Red, but darker", "#8B0000")
+        },
+        PaletteEntry {
+            role_name: Some("The \"Best\" Green".to_string()),
+            ..entry("Green", "#00FF00")
+        },
+        // discord lets role names span several lines.
+        PaletteEntry {
+            role_name: Some("Blue\nbut on two lines".to_string()),
+            ..entry("Blue", "#0000FF")
+        },
+    ];
+
+    for format in &[PaletteFormat::Json, PaletteFormat::Csv] {
+        let data = export_palette(&entries, *format).expect("Error exporting the palette");
+
+        assert_eq!(parse_palette(&data, *format).unwrap(), entries);
+    }
+}
+
+#[test]
+fn palettes_are_checked_when_read() {
+    let parsed = parse_palette("Gold, ffd700\n", PaletteFormat::Csv).unwrap();
+    assert_eq!(parsed, vec![entry("Gold", "#FFD700")]);
+
+    let parsed = parse_palette("name,hex\r\n\r\nGold,ffd700\r\n", PaletteFormat::Csv).unwrap();
+    assert_eq!(parsed, vec![entry("Gold", "#FFD700")]);
+
+    let parsed = parse_palette("\u{feff}name,hex\nGold,ffd700", PaletteFormat::Csv).unwrap();
+    assert_eq!(parsed, vec![entry("Gold", "#FFD700")]);
+
+    assert!(parse_palette("name,hex\nGold,notacolour", PaletteFormat::Csv).is_err());
+    assert!(parse_palette(r#"[{"name": " ", "hex": "#fff"}]"#, PaletteFormat::Json).is_err());
+    assert_eq!(PaletteFormat::from_filename("colours.CSV"), Some(PaletteFormat::Csv));
+    assert_eq!(PaletteFormat::from_filename("colours"), None);
+}
+
+#[test]
+fn imports_follow_the_conflict_policy() {
+    do_test_transaction!(|conn| {
+        let existing = find_all(&DB_GUILD, conn).expect(RECORD_MISSING_FAILURE);
+        let entries = vec![
+            entry("red", "#FF0000"),
+            entry("Blue", "#0000FF"),
+            entry("Blue", "#000080"),
+        ];
+        let describe = |plan: Vec<ImportAction>| {
+            plan.iter().map(ImportAction::describe).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            describe(plan_import(entries.clone(), &existing, ConflictPolicy::Skip)),
+            vec!["skip, name taken", "create", "skip, name taken"]
+        );
+        assert_eq!(
+            describe(plan_import(entries.clone(), &existing, ConflictPolicy::Rename)),
+            vec!["create as \"red (2)\"", "create", "create as \"Blue (2)\""]
+        );
+
+        match plan_import(entries, &existing, ConflictPolicy::Overwrite)[0] {
+            ImportAction::Overwrite { ref existing, .. } => assert_eq!(existing.name, "Red"),
+            ref other => panic!("Expected the colour to be overwritten, got {:?}", other),
+        }
+    })
+}
+
+#[test]
+fn overwrites_are_checked_before_anything_is_created() {
+    do_test_transaction!(|conn| {
+        let existing = find_all(&DB_GUILD, conn).expect(RECORD_MISSING_FAILURE);
+        let entries = vec![entry("Blue", "#0000FF"), entry("Red", "#8B0000")];
+        let plan = plan_import(entries, &existing, ConflictPolicy::Overwrite);
+
+        // red's role is gone, so blue shouldn't be created either.
+        let mut guild = MOCK_GUILD_DATA.clone();
+        guild.roles.remove(&RED_COLOUR_ID);
+
+        assert!(apply_import(&plan, &guild, &DB_GUILD, conn).is_err());
+        assert_eq!(find_all(&DB_GUILD, conn).unwrap().len(), existing.len());
+    })
+}
@@ -0,0 +1,141 @@
+use actions::pickers::*;
+use actions::tests::*;
+use bigdecimal::BigDecimal;
+use db::models::{Colour, ColourReaction};
+use serenity::model::id::MessageId;
+use utils;
+
+fn colour(id: u64, name: &str) -> Colour {
+    Colour {
+        id: BigDecimal::from(id),
+        name: name.to_string(),
+        guild_id: DB_GUILD.id.clone(),
+    }
+}
+
+#[test]
+fn emojis_are_assigned_in_name_order() {
+    let colours = (0..25)
+        .map(|i| colour(i, &format!("Colour {:02}", 24 - i)))
+        .collect::<Vec<_>>();
+
+    let pages = assign_emojis(&colours);
+
+    assert_eq!(pages.len(), 2);
+    assert_eq!(pages[0].len(), MAX_PICKER_REACTIONS);
+    assert_eq!(pages[1].len(), 5);
+
+    assert_eq!(pages[0][0].0.name, "Colour 00");
+    assert_eq!(pages[0][0].1, PICKER_EMOJIS[0]);
+    assert_eq!(pages[1][0].0.name, "Colour 20");
+    assert_eq!(pages[1][0].1, PICKER_EMOJIS[0]);
+
+    let message = generate_picker_message(&pages[1]);
+    assert!(message.contains(&format!("{} Colour 24", PICKER_EMOJIS[4])));
+}
+
+#[test]
+fn picker_bindings_survive_in_the_db() {
+    do_test_transaction!(|conn| {
+        let colours = vec![
+            colour(RED_COLOUR_ID.0, "Red"),
+            colour(GREEN_COLOUR_ID.0, "Green"),
+        ];
+        let message = MessageId(1);
+        let pickers = vec![(message, assign_emojis(&colours).remove(0))];
+
+        save_pickers(&DB_GUILD, &pickers, conn).expect("Error saving the pickers");
+
+        assert_eq!(find_picker_messages(&DB_GUILD, conn), vec![message]);
+
+        let green = find_colour_for_reaction(message, PICKER_EMOJIS[0], conn);
+        let red = find_colour_for_reaction(message, PICKER_EMOJIS[1], conn);
+
+        assert_eq!(green.map(|c| c.name), Some("Green".to_string()));
+        assert_eq!(red.map(|c| c.name), Some("Red".to_string()));
+        assert!(find_colour_for_reaction(message, PICKER_EMOJIS[2], conn).is_none());
+
+        save_pickers(&DB_GUILD, &[], conn).expect("Error clearing the pickers");
+
+        assert!(find_picker_messages(&DB_GUILD, conn).is_empty());
+        assert!(find_colour_for_reaction(message, PICKER_EMOJIS[0], conn).is_none());
+    })
+}
+
+#[test]
+fn pickers_keep_their_bindings_between_refreshes() {
+    let red = colour(RED_COLOUR_ID.0, "Red");
+    let green = colour(GREEN_COLOUR_ID.0, "Green");
+    let blue = colour(3, "Blue");
+
+    let binding = |colour: &Colour, message: u64, emoji: &str| ColourReaction {
+        colour_id: colour.id.clone(),
+        message_id: BigDecimal::from(message),
+        emoji: emoji.to_string(),
+    };
+
+    // red was given the second emoji before green and blue were added.
+    let existing = vec![
+        (MessageId(1), vec![binding(&red, 1, PICKER_EMOJIS[1])]),
+        (MessageId(2), vec![]),
+    ];
+
+    let planned = plan_pickers(&[green.clone(), red.clone(), blue.clone()], &existing);
+
+    assert_eq!(planned.len(), 2);
+    assert_eq!(planned[0].0, Some(MessageId(1)));
+    assert_eq!(
+        planned[0].1,
+        vec![
+            (blue.clone(), PICKER_EMOJIS[0]),
+            (red.clone(), PICKER_EMOJIS[1]),
+            (green.clone(), PICKER_EMOJIS[2]),
+        ]
+    );
+    assert!(planned[1].1.is_empty());
+
+    // colours that don't fit on the posted messages go on new ones.
+    let full = vec![(
+        MessageId(1),
+        PICKER_EMOJIS
+            .iter()
+            .enumerate()
+            .map(|(i, emoji)| binding(&colour(100 + i as u64, "Filler"), 1, emoji))
+            .collect(),
+    )];
+    let mut colours = (0..MAX_PICKER_REACTIONS)
+        .map(|i| colour(100 + i as u64, "Filler"))
+        .collect::<Vec<_>>();
+    colours.push(blue.clone());
+
+    let planned = plan_pickers(&colours, &full);
+
+    assert_eq!(planned.len(), 2);
+    assert_eq!(planned[1], (None, vec![(blue, PICKER_EMOJIS[0])]));
+}
+
+#[test]
+fn single_pickers_are_saved_as_they_are_posted() {
+    do_test_transaction!(|conn| {
+        let red = colour(RED_COLOUR_ID.0, "Red");
+        let green = colour(GREEN_COLOUR_ID.0, "Green");
+
+        save_picker(&DB_GUILD, MessageId(1), &[(red.clone(), PICKER_EMOJIS[0])], conn)
+            .expect("Error saving the picker");
+        save_picker(&DB_GUILD, MessageId(2), &[(green, PICKER_EMOJIS[0])], conn)
+            .expect("Error saving the picker");
+
+        assert_eq!(find_picker_messages(&DB_GUILD, conn).len(), 2);
+
+        // saving the message again replaces its bindings.
+        save_picker(&DB_GUILD, MessageId(1), &[(red, PICKER_EMOJIS[3])], conn)
+            .expect("Error saving the picker");
+
+        assert!(find_colour_for_reaction(MessageId(1), PICKER_EMOJIS[0], conn).is_none());
+        assert_eq!(
+            find_colour_for_reaction(MessageId(1), PICKER_EMOJIS[3], conn).map(|c| c.name),
+            Some("Red".to_string())
+        );
+        assert_eq!(find_picker_bindings(&DB_GUILD, conn)[1].1.len(), 1);
+    })
+}
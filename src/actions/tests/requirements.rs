@@ -0,0 +1,75 @@
+use actions::colours::find_from_role_id;
+use actions::requirements::*;
+use actions::tests::*;
+use bigdecimal::BigDecimal;
+use db::models::{ColourRequirement, GuildSettings};
+use serenity::model::id::RoleId;
+use utils;
+
+const DAY: i64 = 24 * 60 * 60;
+const BOOSTER_ROLE: RoleId = RoleId(484529706037805057);
+
+fn requirement() -> ColourRequirement {
+    ColourRequirement {
+        colour_id: BigDecimal::from(RED_COLOUR_ID.0),
+        role_ids: vec![BigDecimal::from(EXAMPLE_ROLE_ID.0)],
+        boosters_only: true,
+        min_days: 7,
+    }
+}
+
+#[test]
+fn members_meeting_every_requirement_are_let_through() {
+    let roles = [EXAMPLE_ROLE_ID, BOOSTER_ROLE];
+
+    assert!(unmet_requirements(&requirement(), &roles, Some(BOOSTER_ROLE), Some(0), 7 * DAY).is_empty());
+}
+
+#[test]
+fn missing_requirements_are_listed() {
+    let unmet = unmet_requirements(&requirement(), &[], Some(BOOSTER_ROLE), Some(0), 6 * DAY);
+
+    assert_eq!(
+        unmet,
+        vec![
+            Requirement::Roles(vec![EXAMPLE_ROLE_ID]),
+            Requirement::Booster,
+            Requirement::DaysInServer(7),
+        ]
+    );
+
+    // without a booster role nobody can be a booster.
+    let unmet = unmet_requirements(&requirement(), &[EXAMPLE_ROLE_ID], None, Some(0), 7 * DAY);
+    assert_eq!(unmet, vec![Requirement::Booster]);
+}
+
+#[test]
+fn the_booster_role_setting_overrides_the_name() {
+    let mut settings = GuildSettings::default();
+
+    // the mock guild has no role discord made for boosters.
+    assert_eq!(find_booster_role(&MOCK_GUILD_DATA, &settings), None);
+
+    settings.booster_role = Some(EXAMPLE_ROLE_ID.0);
+    assert_eq!(find_booster_role(&MOCK_GUILD_DATA, &settings), Some(EXAMPLE_ROLE_ID));
+
+    // a deleted role doesn't count.
+    settings.booster_role = Some(BOOSTER_ROLE.0);
+    assert_eq!(find_booster_role(&MOCK_GUILD_DATA, &settings), None);
+}
+
+#[test]
+fn empty_requirements_are_removed() {
+    do_test_transaction!(|conn| {
+        let red = find_from_role_id(RED_COLOUR_ID, conn).expect(RECORD_MISSING_FAILURE);
+
+        save_requirement(&requirement(), conn).expect("Error saving the requirement");
+        assert_eq!(find_for_colour(&red, conn), Some(requirement()));
+        assert_eq!(find_locked_ids(&DB_GUILD, conn), vec![red.id.clone()]);
+
+        save_requirement(&ColourRequirement::none_for(red.id.clone()), conn)
+            .expect("Error clearing the requirement");
+        assert!(find_for_colour(&red, conn).is_none());
+        assert!(find_for_guild(&DB_GUILD, conn).is_empty());
+    })
+}
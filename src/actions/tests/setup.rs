@@ -0,0 +1,30 @@
+use actions::setup::*;
+use actions::tests::*;
+use serenity::model::id::UserId;
+
+#[test]
+fn missing_colour_channel_is_reported() {
+    let problems = check_guild(&MOCK_GUILD_DATA, UserId(1), &[], None);
+
+    assert!(problems.contains(&SetupProblem::NoColourChannel));
+}
+
+#[test]
+fn checklist_lists_every_problem() {
+    let problems = vec![
+        SetupProblem::MissingPermission("Manage Roles"),
+        SetupProblem::RolesAboveBot(vec!["Red".to_string(), "Green".to_string()]),
+        SetupProblem::NoColourChannel,
+    ];
+
+    let checklist = generate_checklist("Test", &problems, "!c");
+
+    assert_eq!(checklist.matches("- [ ]").count(), problems.len());
+    assert!(checklist.contains("Manage Roles"));
+    assert!(checklist.contains("Red, Green"));
+    assert!(checklist.contains("`!c setchannel`"));
+    assert!(checklist.contains("`!c doctor`"));
+
+    let all_good = generate_checklist("Test", &[], "!c");
+    assert!(!all_good.contains("- [ ]"));
+}
@@ -0,0 +1,38 @@
+use actions::colours::find_from_role_id;
+use actions::temporary::*;
+use actions::tests::*;
+use serenity::model::id::UserId;
+use utils;
+
+const USER: UserId = UserId(482110165651554330);
+
+#[test]
+fn durations_are_split_off_colour_names() {
+    assert_eq!(split_duration("gold for 24h"), ("gold".to_string(), Some(24 * 60 * 60)));
+    assert_eq!(split_duration("light blue FOR 2 hours"), ("light blue".to_string(), Some(2 * 60 * 60)));
+    assert_eq!(split_duration("for 10m"), ("for 10m".to_string(), None));
+    assert_eq!(split_duration("colour for you"), ("colour for you".to_string(), None));
+
+    assert!(check_duration(1).is_err());
+    assert!(check_duration(60 * 60).is_ok());
+}
+
+#[test]
+fn temporary_colours_expire_and_can_be_cancelled() {
+    do_test_transaction!(|conn| {
+        let red = find_from_role_id(RED_COLOUR_ID, conn).expect(RECORD_MISSING_FAILURE);
+        let green = find_from_role_id(GREEN_COLOUR_ID, conn).expect(RECORD_MISSING_FAILURE);
+
+        save_temporary_colour(&DB_GUILD, USER, &red, &[GREEN_COLOUR_ID], 100, conn)
+            .expect("Error saving the temporary colour");
+        let temporary = save_temporary_colour(&DB_GUILD, USER, &green, &[], 200, conn)
+            .expect("Error replacing the temporary colour");
+
+        assert_eq!(find_for_user(&DB_GUILD, USER, conn), Some(temporary));
+        assert!(find_expired(199, conn).unwrap().is_empty());
+        assert_eq!(find_expired(200, conn).unwrap().len(), 1);
+
+        assert_eq!(cancel_for_user(&DB_GUILD, USER, conn).unwrap(), 1);
+        assert!(find_for_user(&DB_GUILD, USER, conn).is_none());
+    })
+}
@@ -1,277 +1,351 @@
-// use std::sync::{Arc, Mutex};
-// use std::thread::{self, JoinHandle};
-// use std::time::Duration;
-
-// use serenity::framework::standard::CommandError;
-// use serenity::model::channel::{Message, Reaction};
-// use serenity::model::id::{ChannelId, MessageId, UserId};
-
-// use futures::sync::mpsc::{channel, Receiver};
-// use futures::{Future, Stream};
-// use futures_timer::{FutureExt, StreamExt};
-
-// use parallel_event_emitter::{ListenerId, ParallelEventEmitter};
-
-// #[derive(Hash, Clone, Eq, PartialEq)]
-// pub enum CollectorItem {
-//     Message,
-//     Reaction,
-// }
-
-// #[derive(Clone)]
-// pub enum CollectorValue {
-//     Message(Message),
-//     Reaction(Reaction),
-// }
-
-// impl CollectorValue {
-//     fn author_id(&self) -> UserId {
-//         match self {
-//             &CollectorValue::Message(ref msg) => msg.author.id,
-//             &CollectorValue::Reaction(ref react) => react.user_id,
-//         }
-//     }
-
-//     fn channel_id(&self) -> ChannelId {
-//         match self {
-//             &CollectorValue::Message(ref msg) => msg.channel_id,
-//             &CollectorValue::Reaction(ref react) => react.channel_id,
-//         }
-//     }
-
-//     fn message_id(&self) -> MessageId {
-//         match self {
-//             &CollectorValue::Message(ref msg) => msg.id,
-//             &CollectorValue::Reaction(ref react) => react.message_id,
-//         }
-//     }
-// }
-
-// impl From<CollectorValue> for Message {
-//     fn from(value: CollectorValue) -> Self {
-//         match value {
-//             CollectorValue::Message(msg) => msg,
-//             CollectorValue::Reaction(_) => {
-//                 panic!("Invariant! Expected a Message struct but got a reaction.")
-//             }
-//         }
-//     }
-// }
-
-// impl From<CollectorValue> for Reaction {
-//     fn from(value: CollectorValue) -> Self {
-//         match value {
-//             CollectorValue::Message(_) => {
-//                 panic!("Invariant! Expect a Reaction struct but got a message.")
-//             }
-//             CollectorValue::Reaction(react) => react,
-//         }
-//     }
-// }
-
-// pub trait Collectible {
-//     fn collector_type() -> CollectorItem;
-// }
-
-// impl Collectible for Message {
-//     fn collector_type() -> CollectorItem {
-//         CollectorItem::Message
-//     }
-// }
-
-// impl Collectible for Reaction {
-//     fn collector_type() -> CollectorItem {
-//         CollectorItem::Reaction
-//     }
-// }
-
-// pub struct Collector(pub Arc<Mutex<ParallelEventEmitter<CollectorItem>>>);
-
-// impl Collector {
-//     pub fn new() -> Self {
-//         Collector(Arc::new(Mutex::new(ParallelEventEmitter::new())))
-//     }
-
-//     #[allow(dead_code)]
-//     pub fn get_custom(&self) -> CustomCollector {
-//         CustomCollector::new(self.0.clone())
-//     }
-// }
-
-// struct InnerCustomCollector {
-//     collector: Arc<Mutex<ParallelEventEmitter<CollectorItem>>>,
-//     listener_id: Option<ListenerId>,
-//     target_channel: Option<ChannelId>,
-//     target_user: Option<UserId>,
-//     target_message: Option<MessageId>,
-//     limit: usize,
-//     // count towards the limit so we know when to disconnect the listener.
-//     count: usize,
-// }
-
-// #[derive(Clone)]
-// pub struct CustomCollector {
-//     inner: Arc<Mutex<InnerCustomCollector>>,
-// }
-
-// macro_rules! get_inner {
-//     ($inn:expr) => {{
-//         $inn.lock().expect("Error locking inner in get_inner!")
-//     }};
-// }
-
-// #[allow(dead_code)]
-// impl CustomCollector {
-//     pub fn new(collector: Arc<Mutex<ParallelEventEmitter<CollectorItem>>>) -> Self {
-//         CustomCollector {
-//             inner: Arc::new(Mutex::new(InnerCustomCollector {
-//                 collector,
-//                 listener_id: None,
-//                 target_channel: None,
-//                 target_user: None,
-//                 target_message: None,
-//                 limit: 1,
-//                 count: 0,
-//             })),
-//         }
-//     }
-
-//     /// Collector will only get messages from this channel.
-//     pub fn set_channel(&self, chan: ChannelId) -> &Self {
-//         let mut inner = get_inner!(self.inner);
-//         inner.target_channel = Some(chan);
-
-//         self
-//     }
-
-//     /// Collector will only get messages form this user.
-//     pub fn set_author(&self, user: UserId) -> &Self {
-//         let mut inner = get_inner!(self.inner);
-//         inner.target_user = Some(user);
-
-//         self
-//     }
-
-//     pub fn set_message(&self, msg: MessageId) -> &Self {
-//         let mut inner = get_inner!(self.inner);
-//         inner.target_message = Some(msg);
-
-//         self
-//     }
-
-//     pub fn set_limit(&self, limit: usize) -> &Self {
-//         let mut inner = get_inner!(self.inner);
-//         inner.limit = limit;
-
-//         self
-//     }
-
-//     pub fn start_collecting<T: From<CollectorValue> + Collectible + Clone + 'static>(
-//         self,
-//     ) -> Receiver<T> {
-//         let inner = self.inner.clone();
-//         let mut inner = inner
-//             .lock()
-//             .expect("Error locking inner in CustomCollector::start_collecting");
-
-//         let (sender, receiver) = channel(inner.limit);
-
-//         let sender = Arc::new(Mutex::new(sender));
-
-//         let inner_collector = inner.collector.clone();
-//         let mut inner_collector = inner_collector.lock().expect("Error getting collector");
-
-//         let self_arc = Arc::new(self);
-
-//         let id = inner_collector
-//             .add_listener_value(T::collector_type(), move |value: Option<CollectorValue>| {
-//                 let self_arc = self_arc.clone();
-
-//                 let inner = self_arc.inner.clone();
-//                 let mut inner = inner
-//                     .try_lock()
-//                     .expect("Error locking inner in CustomCollector::start_collecting");
-
-//                 inner.count += 1;
-
-//                 let sender = sender.clone();
-//                 let mut sender = sender
-//                     .try_lock()
-//                     .expect("Error locking owned sender in CustomCollector::start_collecting");
-
-//                 let value = value
-//                     .expect("Invariant: Listener did not emit a value for CollectorItem. Fatal.");
-
-//                 let correct_channel = inner
-//                     .target_channel
-//                     .map(|channel| channel == value.channel_id())
-//                     .unwrap_or(true);
-
-//                 let correct_user = inner
-//                     .target_user
-//                     .map(|user| value.author_id() == user)
-//                     .unwrap_or(true);
-
-//                 let correct_message = inner
-//                     .target_message
-//                     .map(|msg| value.message_id() == msg)
-//                     .unwrap_or(true);
-
-//                 // if you remove the (+ 1) the emitter doesn't collect enough messages so dont do that thanks
-//                 if inner.count > inner.limit + 1 {
-//                     let self_arc = self_arc.clone();
-//                     let inner = self_arc.inner.clone();
-
-//                     thread::spawn(move || {
-//                         println!("trying to remove");
-//                         let inner = inner.lock().unwrap();
-
-//                         let mut collector = inner.collector.lock().expect(
-//                             "Error locking collector in ParallelEventEmitter::add_listener_value",
-//                         );
-
-//                         match collector.remove_listener(T::collector_type(), inner.listener_id.unwrap()) {
-//                             Ok(true) => (),
-//                             Ok(false) => panic!("Listener Invariant. remove_listener removed a listener that was already removed"),
-//                             Err(e) => panic!("Error removing listener {}", e),
-//                         }
-//                     });
-//                 } else if correct_channel && correct_user && correct_message {
-//                     match sender.try_send(T::from(value)) {
-//                         Ok(_) => (),
-//                         // channel probably timed out and got dropped.
-//                         Err(_) => (),
-//                     }
-//                 }
-
-//                 Ok(())
-//             })
-//             .expect("Error while adding event listener to collector.");
-
-//         inner.listener_id = Some(id);
-
-//         receiver
-//     }
-
-//     pub fn get_message_reply(self, msg: &Message) -> JoinHandle<Option<Message>> {
-//         let msg = msg.clone();
-//         thread::spawn(move || {
-//             self.set_channel(msg.channel_id.clone())
-//                 .set_limit(1)
-//                 .set_author(msg.author.id.clone());
-
-//             let result = self.start_collecting::<Message>()
-//                 .take(1)
-//                 .map_err(|_| CommandError(format!("Error getting next message.")))
-//                 .timeout(Duration::from_secs(15))
-//                 .collect()
-//                 .wait()
-//                 .map(|val| val.first().map(Message::clone));
-
-//             return match result {
-//                 Ok(reply @ Some(_)) => reply,
-//                 _ => None,
-//             };
-//         })
-//     }
-// }
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Sender};
+use std::time::Duration;
+
+use parking_lot::Mutex;
+
+use serenity::framework::standard::CommandError;
+use serenity::model::channel::{Message, Reaction, ReactionType};
+use serenity::model::id::{ChannelId, MessageId, UserId};
+
+use constants::collector::MAX_LISTENERS;
+use emotes;
+use COLLECTOR;
+
+/// Seconds a command waits for a reply before giving up.
+pub const REPLY_TIMEOUT: u64 = 15;
+
+/// Told to users when every listener slot is taken.
+const FULL_ERROR: &str = "Too many questions are waiting for an answer, try again in a moment.";
+
+/// Replies that count as confirming a question.
+const CONFIRM_REPLIES: [&str; 4] = ["yes", "y", "confirm", "ok"];
+
+/// Given instead of a value when every listener slot is taken.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct CollectorFull;
+
+#[derive(Clone, Debug)]
+pub enum CollectorValue {
+    Message(Message),
+    Reaction(Reaction),
+}
+
+impl CollectorValue {
+    fn author_id(&self) -> UserId {
+        match *self {
+            CollectorValue::Message(ref msg) => msg.author.id,
+            CollectorValue::Reaction(ref react) => react.user_id,
+        }
+    }
+
+    fn channel_id(&self) -> ChannelId {
+        match *self {
+            CollectorValue::Message(ref msg) => msg.channel_id,
+            CollectorValue::Reaction(ref react) => react.channel_id,
+        }
+    }
+}
+
+/// What a listener is waiting for, anything left as `None` matches every value.
+#[derive(Clone, Default, Debug)]
+pub struct CollectorFilter {
+    user: Option<UserId>,
+    channel: Option<ChannelId>,
+    /// Only applies to reactions, messages never belong to another message.
+    message: Option<MessageId>,
+}
+
+impl CollectorFilter {
+    pub fn new() -> Self {
+        CollectorFilter::default()
+    }
+
+    /// Only collect from this user.
+    pub fn user(mut self, user: UserId) -> Self {
+        self.user = Some(user);
+        self
+    }
+
+    /// Only collect from this channel.
+    pub fn channel(mut self, channel: ChannelId) -> Self {
+        self.channel = Some(channel);
+        self
+    }
+
+    /// Only collect reactions on this message.
+    pub fn message(mut self, message: MessageId) -> Self {
+        self.message = Some(message);
+        self
+    }
+
+    pub fn matches(&self, value: &CollectorValue) -> bool {
+        let correct_user = self.user.map(|user| user == value.author_id()).unwrap_or(true);
+        let correct_channel = self
+            .channel
+            .map(|channel| channel == value.channel_id())
+            .unwrap_or(true);
+        let correct_message = match (self.message, value) {
+            (Some(message), &CollectorValue::Reaction(ref react)) => message == react.message_id,
+            _ => true,
+        };
+
+        correct_user && correct_channel && correct_message
+    }
+}
+
+struct Listener {
+    id: usize,
+    filter: CollectorFilter,
+    sender: Sender<CollectorValue>,
+}
+
+/// Hands messages and reactions from the event handler over to commands waiting on a reply.
+pub struct Collector {
+    listeners: Mutex<Vec<Listener>>,
+    next_id: AtomicUsize,
+}
+
+impl Collector {
+    pub fn new() -> Self {
+        Collector {
+            listeners: Mutex::new(vec![]),
+            next_id: AtomicUsize::new(0),
+        }
+    }
+
+    /// Gives the value to the oldest listener waiting for it.
+    /// Returns true if a listener took it, in which case the event handler should leave it alone.
+    pub fn emit(&self, value: CollectorValue) -> bool {
+        let mut listeners = self.listeners.lock();
+
+        match listeners.iter().position(|l| l.filter.matches(&value)) {
+            // the receiver is gone if it timed out right before this, let the handler deal with the value then.
+            Some(index) => listeners.remove(index).sender.send(value).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Whether another listener would be refused right now.
+    pub fn is_full(&self) -> bool {
+        self.listeners.lock().len() >= MAX_LISTENERS
+    }
+
+    /// Blocks until a value matching the filter comes in, or gives up after the timeout.
+    /// Every listener holds on to one of serenity's event threads while it waits,
+    /// so once `MAX_LISTENERS` are open new ones are refused instead of starving the event handler.
+    pub fn wait_for(
+        &self,
+        filter: CollectorFilter,
+        timeout: Duration,
+    ) -> Result<Option<CollectorValue>, CollectorFull> {
+        let (sender, receiver) = channel();
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+
+        {
+            let mut listeners = self.listeners.lock();
+
+            if listeners.len() >= MAX_LISTENERS {
+                return Err(CollectorFull);
+            }
+
+            listeners.push(Listener { id, filter, sender });
+        }
+
+        let value = receiver.recv_timeout(timeout).ok();
+
+        self.listeners.lock().retain(|listener| listener.id != id);
+
+        Ok(value)
+    }
+}
+
+/// Asks the author of `msg` a yes or no question, answered by reacting or replying.
+/// Times out with an error so the command stops without doing anything.
+pub fn ask_confirmation(msg: &Message, question: &str) -> Result<bool, CommandError> {
+    check_open()?;
+
+    let prompt = msg.channel_id.say(format!(
+        "{}\nReact with {} or reply `yes` to confirm, anything else cancels.",
+        question,
+        emotes::GREEN_TICK
+    ))?;
+
+    let _ = prompt.react(emotes::GREEN_TICK);
+    let _ = prompt.react(emotes::RED_CROSS);
+
+    let filter = CollectorFilter::new()
+        .user(msg.author.id)
+        .channel(msg.channel_id)
+        .message(prompt.id);
+
+    let answer = wait_for_answer(&prompt, filter)?;
+
+    let confirmed = match answer {
+        CollectorValue::Reaction(reaction) => {
+            reaction.emoji == ReactionType::Unicode(emotes::GREEN_TICK.to_string())
+        }
+        CollectorValue::Message(reply) => {
+            let _ = reply.delete();
+            CONFIRM_REPLIES.contains(&reply.content.trim().to_lowercase().as_str())
+        }
+    };
+
+    let _ = prompt.delete();
+
+    Ok(confirmed)
+}
+
+/// Asks the author of `msg` to pick one of the options, by reacting with its number or replying with it.
+/// Only the first few options can be picked, as many as there are number emojis.
+pub fn ask_choice(msg: &Message, question: &str, options: &[String]) -> Result<usize, CommandError> {
+    check_open()?;
+
+    let options = &options[..options.len().min(emotes::NUMBERS.len())];
+
+    let list = options
+        .iter()
+        .zip(emotes::NUMBERS.iter())
+        .map(|(option, number)| format!("{} {}", number, option))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let prompt = msg
+        .channel_id
+        .say(format!("{}\n\n{}\n\nReact or reply with a number to pick.", question, list))?;
+
+    for number in &emotes::NUMBERS[..options.len()] {
+        let _ = prompt.react(number.to_string());
+    }
+
+    let filter = CollectorFilter::new()
+        .user(msg.author.id)
+        .channel(msg.channel_id)
+        .message(prompt.id);
+
+    let answer = wait_for_answer(&prompt, filter)?;
+
+    let picked = match answer {
+        CollectorValue::Reaction(reaction) => emotes::NUMBERS[..options.len()]
+            .iter()
+            .position(|number| reaction.emoji == ReactionType::Unicode(number.to_string())),
+        CollectorValue::Message(reply) => {
+            let _ = reply.delete();
+            reply
+                .content
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|number| *number >= 1 && *number <= options.len())
+                .map(|number| number - 1)
+        }
+    };
+
+    let _ = prompt.delete();
+
+    picked.ok_or_else(|| CommandError("That isn't one of the options, nothing was picked.".to_string()))
+}
+
+/// Refuses to ask anything while every listener slot is taken, so the question doesn't get posted for nothing.
+fn check_open() -> Result<(), CommandError> {
+    if COLLECTOR.is_full() {
+        return Err(CommandError(FULL_ERROR.to_string()));
+    }
+
+    Ok(())
+}
+
+/// Waits on the reply to `prompt`, cleaning it up if nothing comes of it.
+fn wait_for_answer(prompt: &Message, filter: CollectorFilter) -> Result<CollectorValue, CommandError> {
+    match COLLECTOR.wait_for(filter, Duration::from_secs(REPLY_TIMEOUT)) {
+        Ok(answer) => answer.ok_or_else(reply_error_closure!(prompt)),
+        // another prompt took the last slot between checking and posting this one.
+        Err(CollectorFull) => {
+            let _ = prompt.delete();
+            Err(CommandError(FULL_ERROR.to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    use serde_json;
+
+    fn reaction(user: u64, message: u64) -> Reaction {
+        let data = format!(
+            r#"{{ "channel_id": "1", "emoji": {{ "name": "✅" }}, "message_id": "{}", "user_id": "{}" }}"#,
+            message, user
+        );
+
+        serde_json::from_str(&data).expect("Error building a reaction")
+    }
+
+    #[test]
+    fn filters_only_match_their_targets() {
+        let filter = CollectorFilter::new()
+            .user(UserId(2))
+            .message(MessageId(3));
+
+        assert!(filter.matches(&CollectorValue::Reaction(reaction(2, 3))));
+        assert!(!filter.matches(&CollectorValue::Reaction(reaction(4, 3))));
+        assert!(!filter.matches(&CollectorValue::Reaction(reaction(2, 5))));
+    }
+
+    #[test]
+    fn waiting_listeners_get_emitted_values() {
+        let collector = Arc::new(Collector::new());
+        let filter = CollectorFilter::new().user(UserId(2));
+
+        assert!(!collector.emit(CollectorValue::Reaction(reaction(2, 3))));
+
+        let waiting = collector.clone();
+        let handle = thread::spawn(move || waiting.wait_for(filter, Duration::from_secs(5)).unwrap());
+
+        // give the listener a moment to register.
+        while collector.listeners.lock().is_empty() {
+            thread::yield_now();
+        }
+
+        assert!(!collector.emit(CollectorValue::Reaction(reaction(4, 3))));
+        assert!(collector.emit(CollectorValue::Reaction(reaction(2, 3))));
+
+        let value = handle.join().expect("Error joining the listener");
+        assert!(value.is_some());
+        assert!(collector.listeners.lock().is_empty());
+    }
+
+    #[test]
+    fn listeners_time_out() {
+        let collector = Collector::new();
+
+        let value = collector.wait_for(CollectorFilter::new(), Duration::from_millis(10));
+
+        assert_eq!(value.map(|v| v.is_none()), Ok(true));
+        assert!(collector.listeners.lock().is_empty());
+    }
+
+    #[test]
+    fn full_collectors_refuse_listeners() {
+        let collector = Collector::new();
+
+        for id in 0..MAX_LISTENERS {
+            let (sender, _) = channel();
+            collector.listeners.lock().push(Listener {
+                id,
+                filter: CollectorFilter::new(),
+                sender,
+            });
+        }
+
+        assert!(collector.is_full());
+
+        let value = collector.wait_for(CollectorFilter::new(), Duration::from_millis(10));
+
+        assert_eq!(value.map(|v| v.is_none()), Err(CollectorFull));
+        assert_eq!(collector.listeners.lock().len(), MAX_LISTENERS);
+    }
+}
@@ -0,0 +1,215 @@
+//! Colour distance metrics.
+//! CIEDE2000 is used by default since plain RGB distance doesn't line up with how different colours look,
+//! the RGB metric is kept around to compare against.
+use palette::pixel::Srgb;
+use palette::Lab;
+
+use std::f64::consts::PI;
+
+/// The metric used to measure how far apart two colours are.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DistanceMetric {
+    /// Squared euclidean distance between the sRGB channels.
+    Rgb,
+    /// CIEDE2000 distance in CIELAB. A distance of ~1 is the smallest difference most people can see.
+    Ciede2000,
+}
+
+impl Default for DistanceMetric {
+    fn default() -> Self {
+        DistanceMetric::Ciede2000
+    }
+}
+
+/// A colour in CIELAB, with `l` in 0..100 and `a`/`b` roughly in -128..128.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CieLab {
+    pub l: f64,
+    pub a: f64,
+    pub b: f64,
+}
+
+impl CieLab {
+    /// Converts an 8 bit sRGB colour into CIELAB (D65 white point).
+    pub fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+        let lab: Lab<f64> = Srgb::new_u8(r, g, b).to_linear().into();
+
+        // palette keeps lab normalised to 0..1 / -1..1, scale it back to the usual ranges.
+        CieLab {
+            l: lab.l * 100.0,
+            a: lab.a * 128.0,
+            b: lab.b * 128.0,
+        }
+    }
+}
+
+/// Squared euclidean distance between two sRGB colours.
+#[cfg_attr(rustfmt, rustfmt_skip)]
+pub fn rgb_distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> f64 {
+      (f64::from(a.0) - f64::from(b.0)).powi(2)
+    + (f64::from(a.1) - f64::from(b.1)).powi(2)
+    + (f64::from(a.2) - f64::from(b.2)).powi(2)
+}
+
+fn hue_angle(b: f64, a: f64) -> f64 {
+    if a == 0.0 && b == 0.0 {
+        0.0
+    } else {
+        let angle = b.atan2(a).to_degrees();
+
+        if angle < 0.0 {
+            angle + 360.0
+        } else {
+            angle
+        }
+    }
+}
+
+/// CIEDE2000 colour difference, following Sharma, Wu and Dalal (2005).
+/// http://www2.ece.rochester.edu/~gsharma/ciede2000/ciede2000noteCRNA.pdf
+pub fn ciede2000(first: &CieLab, second: &CieLab) -> f64 {
+    let pow7 = |x: f64| x.powi(7);
+    let twenty_five_pow7 = pow7(25.0);
+
+    let c1 = (first.a.powi(2) + first.b.powi(2)).sqrt();
+    let c2 = (second.a.powi(2) + second.b.powi(2)).sqrt();
+    let c_mean = (c1 + c2) / 2.0;
+
+    let g = 0.5 * (1.0 - (pow7(c_mean) / (pow7(c_mean) + twenty_five_pow7)).sqrt());
+
+    let a1 = (1.0 + g) * first.a;
+    let a2 = (1.0 + g) * second.a;
+
+    let c1 = (a1.powi(2) + first.b.powi(2)).sqrt();
+    let c2 = (a2.powi(2) + second.b.powi(2)).sqrt();
+
+    let h1 = hue_angle(first.b, a1);
+    let h2 = hue_angle(second.b, a2);
+
+    let delta_l = second.l - first.l;
+    let delta_c = c2 - c1;
+
+    let delta_h = if c1 * c2 == 0.0 {
+        0.0
+    } else if (h2 - h1).abs() <= 180.0 {
+        h2 - h1
+    } else if h2 - h1 > 180.0 {
+        h2 - h1 - 360.0
+    } else {
+        h2 - h1 + 360.0
+    };
+
+    let delta_big_h = 2.0 * (c1 * c2).sqrt() * (delta_h / 2.0).to_radians().sin();
+
+    let l_mean = (first.l + second.l) / 2.0;
+    let c_mean = (c1 + c2) / 2.0;
+
+    let h_mean = if c1 * c2 == 0.0 {
+        h1 + h2
+    } else if (h1 - h2).abs() <= 180.0 {
+        (h1 + h2) / 2.0
+    } else if h1 + h2 < 360.0 {
+        (h1 + h2 + 360.0) / 2.0
+    } else {
+        (h1 + h2 - 360.0) / 2.0
+    };
+
+    let t = 1.0 - 0.17 * (h_mean - 30.0).to_radians().cos()
+        + 0.24 * (2.0 * h_mean).to_radians().cos()
+        + 0.32 * (3.0 * h_mean + 6.0).to_radians().cos()
+        - 0.20 * (4.0 * h_mean - 63.0).to_radians().cos();
+
+    let delta_theta = 30.0 * (-((h_mean - 275.0) / 25.0).powi(2)).exp();
+    let r_c = 2.0 * (pow7(c_mean) / (pow7(c_mean) + twenty_five_pow7)).sqrt();
+
+    let s_l = 1.0 + (0.015 * (l_mean - 50.0).powi(2)) / (20.0 + (l_mean - 50.0).powi(2)).sqrt();
+    let s_c = 1.0 + 0.045 * c_mean;
+    let s_h = 1.0 + 0.015 * c_mean * t;
+    let r_t = -(2.0 * delta_theta * PI / 180.0).sin() * r_c;
+
+    let l_term = delta_l / s_l;
+    let c_term = delta_c / s_c;
+    let h_term = delta_big_h / s_h;
+
+    (l_term.powi(2) + c_term.powi(2) + h_term.powi(2) + r_t * c_term * h_term).sqrt()
+}
+
+/// Puts a CIEDE2000 distance into words, for telling users how close a match is.
+pub fn describe_distance(distance: f64) -> &'static str {
+    if distance < 1.0 {
+        "practically identical"
+    } else if distance < 3.0 {
+        "very close"
+    } else if distance < 10.0 {
+        "close"
+    } else if distance < 25.0 {
+        "somewhat close"
+    } else {
+        "not very close"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn lab(l: f64, a: f64, b: f64) -> CieLab {
+        CieLab { l, a, b }
+    }
+
+    #[test]
+    pub fn ciede2000_matches_reference_pairs() {
+        // test data from the Sharma, Wu and Dalal paper.
+        let pairs = vec![
+            (lab(50.0, 2.6772, -79.7751), lab(50.0, 0.0, -82.7485), 2.0425),
+            (lab(50.0, 3.1571, -77.2803), lab(50.0, 0.0, -82.7485), 2.8615),
+            (lab(50.0, 2.8361, -74.0200), lab(50.0, 0.0, -82.7485), 3.4412),
+            (lab(50.0, 0.0, 0.0), lab(50.0, -1.0, 2.0), 2.3669),
+            (lab(50.0, 2.5, 0.0), lab(73.0, 25.0, -18.0), 27.1492),
+            (lab(60.2574, -34.0099, 36.2677), lab(60.4626, -34.1751, 39.4387), 1.2644),
+            (lab(22.7233, 20.0904, -46.6940), lab(23.0331, 14.9730, -42.5619), 2.0373),
+            (lab(90.8027, -2.0831, 1.4410), lab(91.1528, -1.6435, 0.0447), 1.4441),
+        ];
+
+        for (first, second, expected) in pairs {
+            let distance = ciede2000(&first, &second);
+
+            assert!(
+                (distance - expected).abs() < 0.0001,
+                "Expected {} between {:?} and {:?}, got {}",
+                expected,
+                first,
+                second,
+                distance
+            );
+
+            let reversed = ciede2000(&second, &first);
+
+            assert!(
+                (distance - reversed).abs() < 0.0001,
+                "CIEDE2000 should be symmetric"
+            );
+        }
+    }
+
+    #[test]
+    pub fn identical_colours_have_no_distance() {
+        let colour = CieLab::from_rgb(12, 200, 99);
+
+        assert_eq!(ciede2000(&colour, &colour), 0.0);
+    }
+
+    #[test]
+    pub fn rgb_converts_to_lab() {
+        let white = CieLab::from_rgb(255, 255, 255);
+        let red = CieLab::from_rgb(255, 0, 0);
+
+        assert!((white.l - 100.0).abs() < 0.01, "{:?}", white);
+        assert!(white.a.abs() < 0.01 && white.b.abs() < 0.01, "{:?}", white);
+
+        // sRGB red is roughly L 53.24, a 80.09, b 67.20
+        assert!((red.l - 53.24).abs() < 0.05, "{:?}", red);
+        assert!((red.a - 80.09).abs() < 0.05, "{:?}", red);
+        assert!((red.b - 67.20).abs() < 0.05, "{:?}", red);
+    }
+}
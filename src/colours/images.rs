@@ -1,11 +1,14 @@
+use std::fmt::{Display, Error as FmtError, Formatter};
 use std::fs::File;
+use std::io::prelude::*;
 use std::io::Error as IoError;
 use std::path::PathBuf;
+use std::str::FromStr;
 
 use colours::{models::SortMethod, ParsedColour};
 use serenity::utils::Colour;
 
-use cairo::IoError as CairoIoError;
+use cairo::{BorrowError as CairoBorrowError, ImageSurface, IoError as CairoIoError};
 
 use resvg;
 use resvg::Error as ReSvgError;
@@ -15,34 +18,259 @@ use svg::node::element::{Group, Rectangle, Text as TextEl};
 use svg::node::Text;
 use svg::Document;
 
+use webp::Encoder as WebpEncoder;
+
 const DARK_THEME_BACKGROUND: &str = "#36393e";
+const LIGHT_THEME_BACKGROUND: &str = "#ffffff";
+const AMOLED_THEME_BACKGROUND: &str = "#000000";
+
+/// WCAG's minimum contrast for large text, names below this are marked as hard to read.
+pub const MIN_CONTRAST_RATIO: f64 = 3.0;
+const CONTRAST_MARKER: &str = "[!]";
+/// Shown next to colours that have requirements before they can be picked.
+const LOCKED_MARKER: &str = "[locked]";
+/// Hex codes are always `#RRGGBB`, drawn smaller than the names.
+const HEX_CODE_LENGTH: usize = 7;
+const HEX_CODE_FONT_SCALE: f64 = 0.7;
+/// A rough average character width compared to the font size, text can't be measured before it's rendered.
+const AVERAGE_CHAR_WIDTH: f64 = 0.6;
+/// Space kept between the edges of a column and the text in it.
+const LIST_TEXT_PADDING: f64 = 10.0;
+
+pub const DEFAULT_FONT_FAMILY: &str = "Roboto";
+pub const DEFAULT_FONT_SIZE: f64 = 35.0;
+/// Space around each row of the list, on top of the font size.
+const LIST_ROW_PADDING: f64 = 30.0;
+const LIST_COLUMN_WIDTH: usize = 500;
+pub const MAX_LIST_COLUMNS: usize = 3;
 
-const TOP_PADDING: usize = 50;
+/// How many colours go on each page of the list by default.
+pub const DEFAULT_PAGE_SIZE: usize = 50;
 
-const FONT_SIZE: f64 = 35.0;
-const LIST_ROW_HEIGHT: usize = 65;
-const LIST_COLUMN_WIDTH: usize = 500;
+const SWATCH_WIDTH: usize = 260;
+const SWATCH_HEIGHT: usize = 150;
+const SWATCH_GAP: usize = 15;
+const SWATCH_FONT_SIZE: f64 = 24.0;
+/// Names longer than this are cut short so they stay inside their swatch.
+const SWATCH_MAX_NAME_LENGTH: usize = 17;
+const SWATCH_MIN_COLUMNS: usize = 2;
+const SWATCH_MAX_COLUMNS: usize = 8;
 
 /// Opaque name type to prevent wrong items being entered into the list.
 #[derive(Clone, Debug)]
 pub struct Name(pub String);
 
-#[derive(Clone)]
+/// A colour in the list, along with the role stats some of the sort methods need.
+#[derive(Clone, Debug)]
+pub struct ListColour {
+    pub name: Name,
+    pub colour: Colour,
+    /// The role id, newer roles have larger ids so this orders colours by when they were added.
+    pub added: u64,
+    /// How many members have the colour.
+    pub usage: usize,
+    /// Whether members need something before they can pick the colour.
+    pub locked: bool,
+}
+
+impl ListColour {
+    pub fn to_parsed(&self) -> ParsedColour {
+        ParsedColour {
+            name: Some(&self.name.0),
+            ..ParsedColour::from(self.colour)
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ColourListType {
     BasicList,
+    SwatchGrid,
+}
+
+impl Display for ColourListType {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+        match *self {
+            ColourListType::BasicList => "basic_list".fmt(f),
+            ColourListType::SwatchGrid => "swatch_grid".fmt(f),
+        }
+    }
+}
+
+impl FromStr for ColourListType {
+    type Err = String;
+
+    fn from_str(list_type: &str) -> Result<Self, Self::Err> {
+        match list_type.trim().to_lowercase().as_str() {
+            "basic_list" | "basic" | "list" => Ok(ColourListType::BasicList),
+            "swatch_grid" | "swatch" | "swatches" | "grid" => Ok(ColourListType::SwatchGrid),
+            other => Err(format!(
+                "\"{}\" is not a list style. Valid styles are: basic_list, swatch_grid",
+                other
+            )),
+        }
+    }
+}
+
+/// The background the list is drawn on.
+/// Extra columns of the basic list show the same colours on the other discord themes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListTheme {
+    Dark,
+    Light,
+    Amoled,
+    /// Any background colour, stored as a hex code.
+    Custom(String),
+}
+
+impl ListTheme {
+    pub fn background(&self) -> &str {
+        match *self {
+            ListTheme::Dark => DARK_THEME_BACKGROUND,
+            ListTheme::Light => LIGHT_THEME_BACKGROUND,
+            ListTheme::Amoled => AMOLED_THEME_BACKGROUND,
+            ListTheme::Custom(ref background) => background,
+        }
+    }
+
+    /// The background for each column, this theme first and then the discord themes it isn't.
+    pub fn column_backgrounds(&self, columns: usize) -> Vec<String> {
+        let others = [ListTheme::Dark, ListTheme::Light, ListTheme::Amoled]
+            .iter()
+            .filter(|theme| *theme != self)
+            .map(|theme| theme.background().to_string())
+            .collect::<Vec<_>>();
+
+        let mut backgrounds = vec![self.background().to_string()];
+        backgrounds.extend(others);
+        backgrounds.truncate(columns);
+
+        backgrounds
+    }
+}
+
+impl Display for ListTheme {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+        match *self {
+            ListTheme::Dark => "dark".fmt(f),
+            ListTheme::Light => "light".fmt(f),
+            ListTheme::Amoled => "amoled".fmt(f),
+            ListTheme::Custom(ref background) => background.fmt(f),
+        }
+    }
+}
+
+impl FromStr for ListTheme {
+    type Err = String;
+
+    fn from_str(theme: &str) -> Result<Self, Self::Err> {
+        match theme.trim().to_lowercase().as_str() {
+            "dark" | "discord" => Ok(ListTheme::Dark),
+            "light" | "white" => Ok(ListTheme::Light),
+            "amoled" | "black" => Ok(ListTheme::Amoled),
+            other => ParsedColour::from_str(other)
+                .map(|colour| ListTheme::Custom(colour.to_string()))
+                .map_err(|_| {
+                    format!(
+                        "\"{}\" is not a theme. Use dark, light, amoled or a background colour like #202225",
+                        other
+                    )
+                }),
+        }
+    }
+}
+
+/// The file format the list is rendered into.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+    Png,
+    /// The raw svg document, stays sharp at any zoom but discord won't preview it.
+    Svg,
+    /// Lossless webp, a lot smaller than the png for the same image.
+    Webp,
+}
+
+impl OutputFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Png => "png",
+            OutputFormat::Svg => "svg",
+            OutputFormat::Webp => "webp",
+        }
+    }
+
+    /// Whether discord shows the file as an image, anything else is only offered as a download.
+    pub fn previews_in_discord(self) -> bool {
+        match self {
+            OutputFormat::Png | OutputFormat::Webp => true,
+            OutputFormat::Svg => false,
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            OutputFormat::Png => "image/png",
+            OutputFormat::Svg => "image/svg+xml",
+            OutputFormat::Webp => "image/webp",
+        }
+    }
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+        self.extension().fmt(f)
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format.trim().trim_left_matches('.').to_lowercase().as_str() {
+            "png" => Ok(OutputFormat::Png),
+            "svg" => Ok(OutputFormat::Svg),
+            "webp" => Ok(OutputFormat::Webp),
+            other => Err(format!(
+                "\"{}\" is not an image format. Valid formats are: png, svg, webp",
+                other
+            )),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub enum ColourBuilderError {
+    CairoBorrow(CairoBorrowError),
     CairoIo(CairoIoError),
     Io(IoError),
     ReSvg(ReSvgError),
 }
 
+/// A rendered page of the colour list, kept in memory so it can be uploaded without touching the disk.
+#[derive(Clone, Debug)]
+pub struct ColourListImage {
+    /// File name the image is uploaded as.
+    pub name: String,
+    pub format: OutputFormat,
+    /// The encoded image.
+    pub data: Vec<u8>,
+}
+
+impl ColourListImage {
+    /// The image in the form serenity takes for attachments.
+    pub fn as_attachment(&self) -> (&[u8], &str) {
+        (&self.data, &self.name)
+    }
+}
+
 #[derive(Debug)]
 pub struct ColourSection {
     pub name: String,
     pub colour: Colour,
+    pub locked: bool,
     pub y: f64,
     pub x: f64,
 }
@@ -53,6 +281,12 @@ impl From<IoError> for ColourBuilderError {
     }
 }
 
+impl From<CairoBorrowError> for ColourBuilderError {
+    fn from(e: CairoBorrowError) -> Self {
+        ColourBuilderError::CairoBorrow(e)
+    }
+}
+
 impl From<CairoIoError> for ColourBuilderError {
     fn from(e: CairoIoError) -> Self {
         ColourBuilderError::CairoIo(e)
@@ -69,43 +303,189 @@ impl From<ReSvgError> for ColourBuilderError {
 /// *TODO:* Figure out how to make cario work on windows, currently cant compile atm, find out a solution and put it in the docs
 pub struct ColourListBuilder {
     list_type: ColourListType,
-    dual_colour: bool,
-    // show_hex_codes: bool,
+    sort_method: SortMethod,
+    page_size: usize,
+    format: OutputFormat,
+    debug_dir: Option<PathBuf>,
+    theme: ListTheme,
+    columns: usize,
+    font_family: String,
+    font_size: f64,
+    show_hex_codes: bool,
+    contrast_warnings: bool,
 }
 
 impl ColourListBuilder {
     pub fn new() -> ColourListBuilder {
         ColourListBuilder {
             list_type: ColourListType::BasicList,
-            dual_colour: true,
-            // show_hex_codes: true,
+            sort_method: SortMethod::HSL,
+            page_size: DEFAULT_PAGE_SIZE,
+            format: OutputFormat::Png,
+            debug_dir: None,
+            theme: ListTheme::Dark,
+            columns: 2,
+            font_family: DEFAULT_FONT_FAMILY.to_string(),
+            font_size: DEFAULT_FONT_SIZE,
+            show_hex_codes: false,
+            contrast_warnings: false,
         }
     }
 
-    // pub fn set_type(&mut self, cl: ColourListType) -> &mut ColourListBuilder {
-    //     self.list_type = cl;
-    //     self
-    // }
+    pub fn set_type(&mut self, cl: ColourListType) -> &mut ColourListBuilder {
+        self.list_type = cl;
+        self
+    }
+
+    pub fn set_sort_method(&mut self, method: SortMethod) -> &mut ColourListBuilder {
+        self.sort_method = method;
+        self
+    }
+
+    /// Sets how many colours go on each image, discord shrinks very tall images until they can't be read.
+    pub fn set_page_size(&mut self, size: usize) -> &mut ColourListBuilder {
+        self.page_size = size.max(1);
+        self
+    }
+
+    pub fn set_format(&mut self, format: OutputFormat) -> &mut ColourListBuilder {
+        self.format = format;
+        self
+    }
+
+    /// Also writes every rendered image into the given directory, for debugging the renderer.
+    pub fn set_debug_dir(&mut self, dir: Option<PathBuf>) -> &mut ColourListBuilder {
+        self.debug_dir = dir;
+        self
+    }
+
+    pub fn set_theme(&mut self, theme: ListTheme) -> &mut ColourListBuilder {
+        self.theme = theme;
+        self
+    }
+
+    /// Sets how many columns the basic list has, each one shows the colours on a different background.
+    pub fn set_columns(&mut self, columns: usize) -> &mut ColourListBuilder {
+        self.columns = columns.max(1).min(MAX_LIST_COLUMNS);
+        self
+    }
+
+    pub fn set_font(&mut self, family: &str, size: f64) -> &mut ColourListBuilder {
+        self.font_family = family.to_string();
+        self.font_size = size;
+        self
+    }
+
+    fn row_height(&self) -> f64 {
+        self.font_size + LIST_ROW_PADDING
+    }
+
+    pub fn set_show_hex_codes(&mut self, st: bool) -> &mut ColourListBuilder {
+        self.show_hex_codes = st;
+        self
+    }
+
+    /// Marks names that are hard to read against the background they're drawn on.
+    pub fn set_contrast_warnings(&mut self, st: bool) -> &mut ColourListBuilder {
+        self.contrast_warnings = st;
+        self
+    }
+
+    /// Whether the colour needs a contrast marker when drawn on the given background.
+    fn needs_contrast_marker(&self, colour: Colour, background: &str) -> bool {
+        self.contrast_warnings && ParsedColour::from_str(background)
+            .map(|background| ParsedColour::from(colour).contrast_ratio(&background) < MIN_CONTRAST_RATIO)
+            .unwrap_or(false)
+    }
+
+    /// How wide the hex codes are at the current font size.
+    fn hex_code_width(&self) -> f64 {
+        HEX_CODE_LENGTH as f64 * self.font_size * HEX_CODE_FONT_SCALE * AVERAGE_CHAR_WIDTH
+    }
+
+    /// How many characters fit in a row of the basic list, leaving room for the hex code when it's shown.
+    pub fn max_label_length(&self) -> usize {
+        let hex_code = if self.show_hex_codes {
+            self.hex_code_width() + LIST_TEXT_PADDING
+        } else {
+            0.0
+        };
+        let space = LIST_COLUMN_WIDTH as f64 - 2.0 * LIST_TEXT_PADDING - hex_code;
+
+        (space / (self.font_size * AVERAGE_CHAR_WIDTH)).max(0.0) as usize
+    }
+
+    /// The name with its markers, cut short so it doesn't run into the hex code or past the column.
+    /// The markers are kept over the name, the theme names are dropped first if they don't fit.
+    /// Only a locked marker can still overflow, at the largest fonts with hex codes shown.
+    pub fn list_label(&self, name: &str, locked: bool, hard_to_read: &[&str]) -> String {
+        let max_length = self.max_label_length();
+
+        let mut markers = String::new();
+
+        if locked {
+            markers.push_str(&format!(" {}", LOCKED_MARKER));
+        }
+
+        if !hard_to_read.is_empty() {
+            let themes = format!(" {} {}", CONTRAST_MARKER, hard_to_read.join("/"));
+
+            if markers.chars().count() + themes.chars().count() < max_length {
+                markers.push_str(&themes);
+            } else {
+                markers.push_str(&format!(" {}", CONTRAST_MARKER));
+            }
+        }
+
+        let name_length = name.chars().count();
+        let markers_length = markers.chars().count();
 
-    // pub fn set_dual_colour(&mut self, st: bool) -> &mut ColourListBuilder {
-    //     self.dual_colour = st;
-    //     self
-    // }
+        if name_length + markers_length <= max_length {
+            format!("{}{}", name, markers)
+        } else {
+            // leave room for the ellipsis.
+            let keep = max_length.saturating_sub(markers_length + 1);
 
-    // pub fn set_show_hex_code(&mut self, st: bool) -> &mut ColourListBuilder {
-    //     self.show_hex_codes = st;
-    //     self
-    // }
+            format!("{}…{}", name.chars().take(keep).collect::<String>(), markers)
+        }
+    }
+
+    /// The discord themes the colour is hard to read on, checked whatever background the list is drawn on.
+    fn hard_to_read_themes(&self, colour: Colour) -> Vec<&'static str> {
+        [("dark", DARK_THEME_BACKGROUND), ("light", LIGHT_THEME_BACKGROUND)]
+            .iter()
+            .filter(|&&(_, background)| self.needs_contrast_marker(colour, background))
+            .map(|&(theme, _)| theme)
+            .collect()
+    }
+
+    /// How many columns of colours the image has. The basic list repeats itself once per column.
+    pub fn get_columns_for_type(&self, amount: usize) -> usize {
+        match self.list_type {
+            ColourListType::BasicList => self.columns,
+            // keep the grid roughly square, so large lists grow wider instead of only taller.
+            ColourListType::SwatchGrid => ((amount as f64).sqrt().ceil() as usize)
+                .max(SWATCH_MIN_COLUMNS)
+                .min(SWATCH_MAX_COLUMNS),
+        }
+    }
 
     pub fn get_height_for_type(&self, amount: usize) -> usize {
         match self.list_type {
-            ColourListType::BasicList => amount * LIST_ROW_HEIGHT as usize,
+            ColourListType::BasicList => (amount as f64 * self.row_height()) as usize,
+            ColourListType::SwatchGrid => {
+                let columns = self.get_columns_for_type(amount);
+                let rows = (amount + columns - 1) / columns;
+
+                rows * (SWATCH_HEIGHT + SWATCH_GAP) + SWATCH_GAP
+            }
         }
     }
 
     pub fn get_width_for_type(&self, columns: usize) -> usize {
         match self.list_type {
             ColourListType::BasicList => LIST_COLUMN_WIDTH * columns,
+            ColourListType::SwatchGrid => columns * (SWATCH_WIDTH + SWATCH_GAP) + SWATCH_GAP,
         }
     }
 
@@ -114,23 +494,37 @@ impl ColourListBuilder {
         name: String,
         colour: Colour,
         height: usize,
-        colours: &[(Name, Colour)],
+        colours: &[ListColour],
     ) -> ColourSection {
         match self.list_type {
             ColourListType::BasicList => {
+                // text is drawn from its baseline, so the first row needs room for the font above it.
                 let top_margin = if height != colours.len() {
-                    TOP_PADDING as f64
+                    self.font_size + LIST_ROW_PADDING / 2.0
                 } else {
                     0.0
                 };
 
-                let full_height = height as f64 * LIST_ROW_HEIGHT as f64;
+                let full_height = height as f64 * self.row_height();
 
                 ColourSection {
                     colour,
                     name,
+                    locked: false,
                     y: full_height + top_margin,
-                    x: 10.0,
+                    x: LIST_TEXT_PADDING,
+                }
+            }
+            ColourListType::SwatchGrid => {
+                let columns = self.get_columns_for_type(colours.len());
+                let (row, column) = (height / columns, height % columns);
+
+                ColourSection {
+                    colour,
+                    name,
+                    locked: false,
+                    y: (SWATCH_GAP + row * (SWATCH_HEIGHT + SWATCH_GAP)) as f64,
+                    x: (SWATCH_GAP + column * (SWATCH_WIDTH + SWATCH_GAP)) as f64,
                 }
             }
         }
@@ -140,27 +534,23 @@ impl ColourListBuilder {
         &self,
         document: Document,
         colours: &[ColourSection],
-        (height, width, _): (usize, usize, usize),
+        (height, _, columns): (usize, usize, usize),
     ) -> Document {
-        let background = Rectangle::new()
-            .set("x", 0)
-            .set("y", 0)
-            .set("width", width)
-            .set("height", height)
-            .set("fill", DARK_THEME_BACKGROUND);
+        let backgrounds = self.theme.column_backgrounds(columns);
 
-        let document = if self.dual_colour {
-            let second = Rectangle::new()
-                .set("x", LIST_COLUMN_WIDTH)
-                .set("y", 0)
-                .set("width", LIST_COLUMN_WIDTH)
-                .set("height", height)
-                .set("fill", "white");
-
-            document.add(background).add(second)
-        } else {
-            document.add(background)
-        };
+        let document = backgrounds
+            .iter()
+            .enumerate()
+            .fold(document, |doc, (column, background)| {
+                doc.add(
+                    Rectangle::new()
+                        .set("x", column * LIST_COLUMN_WIDTH)
+                        .set("y", 0)
+                        .set("width", LIST_COLUMN_WIDTH)
+                        .set("height", height)
+                        .set("fill", background.as_str()),
+                )
+            });
 
         let list = colours
             .iter()
@@ -170,73 +560,206 @@ impl ColourListBuilder {
                     .set("x", section.x)
                     .set("y", section.y)
                     .set("width", LIST_COLUMN_WIDTH)
-                    .set("height", LIST_ROW_HEIGHT);
+                    .set("height", self.row_height());
+
+                let fill = format!("rgb({}, {}, {})", r, g, b);
+
+                // a column only shows one background, but members could be on either theme.
+                let hard_to_read = self.hard_to_read_themes(section.colour);
+
+                let label = self.list_label(&section.name, section.locked, &hard_to_read);
+
+                let column = |x: f64| {
+                    let text = TextEl::new()
+                        .set("x", x)
+                        .set("y", section.y)
+                        .set("width", LIST_COLUMN_WIDTH)
+                        .set("font-family", self.font_family.as_str())
+                        .set("font-size", self.font_size)
+                        .set("fill", fill.clone())
+                        .add(Text::new(label.clone()));
+
+                    let group = Group::new().add(text);
+
+                    // the code is lined up against the right edge, so it grows to the left with the font.
+                    if self.show_hex_codes {
+                        let hex = TextEl::new()
+                            .set("x", x - section.x + LIST_COLUMN_WIDTH as f64 - LIST_TEXT_PADDING)
+                            .set("y", section.y)
+                            .set("text-anchor", "end")
+                            .set("font-family", self.font_family.as_str())
+                            .set("font-size", self.font_size * HEX_CODE_FONT_SCALE)
+                            .set("fill", fill.clone())
+                            .add(Text::new(ParsedColour::from(section.colour).to_string()));
+
+                        group.add(hex)
+                    } else {
+                        group
+                    }
+                };
+
+                backgrounds
+                    .iter()
+                    .enumerate()
+                    .fold(path, |path, (index, _)| {
+                        path.add(column(section.x + (index * LIST_COLUMN_WIDTH) as f64))
+                    })
+            }).collect::<Vec<Group>>();
+
+        list.iter().fold(document, |doc, now| doc.add(now.clone()))
+    }
+
+    pub fn generate_image_for_grid(
+        &self,
+        document: Document,
+        colours: &[ColourSection],
+        (height, width, _): (usize, usize, usize),
+    ) -> Document {
+        let background = Rectangle::new()
+            .set("x", 0)
+            .set("y", 0)
+            .set("width", width)
+            .set("height", height)
+            .set("fill", self.theme.background());
+
+        let grid = colours
+            .iter()
+            .map(|section| {
+                let (r, g, b) = section.colour.tuple();
+                let parsed = ParsedColour::from(section.colour);
+
+                // dark text on light swatches, light text on dark ones.
+                let luma = 0.299 * f64::from(r) + 0.587 * f64::from(g) + 0.114 * f64::from(b);
+                let text_colour = if luma > 140.0 { "black" } else { "white" };
 
-                let text = TextEl::new()
+                let name = if section.name.chars().count() > SWATCH_MAX_NAME_LENGTH {
+                    let short = section
+                        .name
+                        .chars()
+                        .take(SWATCH_MAX_NAME_LENGTH - 1)
+                        .collect::<String>();
+
+                    format!("{}…", short)
+                } else {
+                    section.name.clone()
+                };
+
+                let swatch = Rectangle::new()
                     .set("x", section.x)
                     .set("y", section.y)
-                    .set("width", LIST_COLUMN_WIDTH)
-                    .set("font-family", "Roboto")
-                    .set("font-size", FONT_SIZE)
-                    .set("fill", format!("rgb({}, {}, {})", r, g, b))
-                    .add(Text::new(section.name.clone()));
+                    .set("width", SWATCH_WIDTH)
+                    .set("height", SWATCH_HEIGHT)
+                    .set("rx", 8)
+                    .set("fill", format!("rgb({}, {}, {})", r, g, b));
+
+                let label = |text: String, y: f64, size: f64| {
+                    TextEl::new()
+                        .set("x", section.x + 12.0)
+                        .set("y", y)
+                        .set("font-family", self.font_family.as_str())
+                        .set("font-size", size)
+                        .set("fill", text_colour)
+                        .add(Text::new(text))
+                };
+
+                let bottom = section.y + SWATCH_HEIGHT as f64;
 
-                if self.dual_colour {
-                    let second_text = text
-                        .clone()
-                        .set("x", section.x as usize + LIST_COLUMN_WIDTH);
+                // the swatch shows the colour itself, so mark which discord themes the name is hard to read on.
+                let hard_to_read = self.hard_to_read_themes(section.colour);
 
-                    path.add(second_text).add(text)
+                let code = if hard_to_read.is_empty() {
+                    parsed.to_string()
                 } else {
-                    path.add(text)
-                }
+                    format!("{} {} {}", parsed, CONTRAST_MARKER, hard_to_read.join("/"))
+                };
+
+                // the name can get cut short on a swatch, so the marker goes with the code instead.
+                let code = if section.locked {
+                    format!("{} {}", code, LOCKED_MARKER)
+                } else {
+                    code
+                };
+
+                Group::new()
+                    .add(swatch)
+                    .add(label(name, bottom - 50.0, SWATCH_FONT_SIZE))
+                    .add(label(code, bottom - 18.0, SWATCH_FONT_SIZE * 0.75))
             }).collect::<Vec<Group>>();
 
-        list.iter().fold(document, |doc, now| doc.add(now.clone()))
+        grid.iter().fold(document.add(background), |doc, now| doc.add(now.clone()))
     }
 
-    pub fn transform_colours_to_sections(&self, colours: &[(Name, Colour)]) -> Vec<ColourSection> {
+    pub fn transform_colours_to_sections(&self, colours: &[ListColour]) -> Vec<ColourSection> {
         colours
             .iter()
             .enumerate()
-            .map(|(height, &(ref name, colour))| {
-                self.get_section_from_colour(name.0.clone(), colour, height, colours)
+            .map(|(height, entry)| ColourSection {
+                locked: entry.locked,
+                ..self.get_section_from_colour(entry.name.0.clone(), entry.colour, height, colours)
             }).collect::<Vec<ColourSection>>()
     }
 
-    fn sort_colours(&self, colours: &[(Name, Colour)]) -> Vec<(Name, Colour)> {
-        // get config for the sort type
+    fn sort_colours(&self, colours: &[ListColour]) -> Vec<ListColour> {
+        let mut sorted = colours.to_vec();
 
-        // cheat a little, replace the name in the colour struct with the given name
+        // sort by hue first, so colours tied on the role stats still come out in a sensible order.
+        sorted.sort_by(|a, b| a.to_parsed().compare(&b.to_parsed(), SortMethod::HSL));
 
-        let colours_remade: Vec<ParsedColour> = colours
-            .iter()
-            .map(|(name, colour)| ParsedColour {
-                name: Some(&name.0),
-                ..ParsedColour::from(*colour)
-            }).collect();
+        match self.sort_method {
+            SortMethod::DateAdded => sorted.sort_by_key(|entry| entry.added),
+            SortMethod::UsageCount => sorted.sort_by(|a, b| b.usage.cmp(&a.usage)),
+            SortMethod::HSL => {}
+            method => sorted.sort_by(|a, b| a.to_parsed().compare(&b.to_parsed(), method)),
+        }
+
+        sorted
+    }
 
-        ParsedColour::sort_list(&colours_remade, SortMethod::HSL)
+    /// Sorts the colours and splits them into pages of `page_size` colours.
+    pub fn paginate(&self, colours: &[ListColour]) -> Vec<Vec<ListColour>> {
+        self.sort_colours(colours)
+            .chunks(self.page_size)
+            .map(<[ListColour]>::to_vec)
+            .collect()
+    }
+
+    /// Renders every page of the list, the images are named `<id>-<page>.png`.
+    pub fn create_images<S: Into<String>>(
+        &self,
+        colours: &[ListColour],
+        id: S,
+    ) -> Result<Vec<ColourListImage>, ColourBuilderError> {
+        let id = id.into();
+
+        self.paginate(colours)
             .iter()
-            .map(|colour| {
-                (
-                    Name(colour.name.unwrap().to_string()),
-                    colour.as_role_colour(),
-                )
-            }).collect()
+            .enumerate()
+            .map(|(page, colours)| self.render_page(colours, format!("{}-{}", id, page + 1)))
+            .collect()
     }
 
+    /// Renders the whole list into a single image.
     pub fn create_image<S: Into<String>>(
         &self,
-        colours: &[(Name, Colour)],
+        colours: &[ListColour],
+        id: S,
+    ) -> Result<ColourListImage, ColourBuilderError> {
+        let sorted_colors = self.sort_colours(&colours);
+
+        self.render_page(&sorted_colors, id)
+    }
+
+    /// Renders already sorted colours.
+    fn render_page<S: Into<String>>(
+        &self,
+        colours: &[ListColour],
         id: S,
-    ) -> Result<PathBuf, ColourBuilderError> {
-        let columns = if self.dual_colour { 2 } else { 1 };
+    ) -> Result<ColourListImage, ColourBuilderError> {
+        let columns = self.get_columns_for_type(colours.len());
         let height = self.get_height_for_type(colours.len());
         let width = self.get_width_for_type(columns);
-        let sorted_colors = self.sort_colours(&colours);
 
-        let colours = self.transform_colours_to_sections(&sorted_colors);
+        let colours = self.transform_colours_to_sections(&colours);
 
         let doc = svg::Document::new().set("viewBox", (0, 0, width, height));
 
@@ -244,20 +767,77 @@ impl ColourListBuilder {
             ColourListType::BasicList => {
                 self.generate_image_for_list(doc, &colours, (height, width, columns))
             }
+            ColourListType::SwatchGrid => {
+                self.generate_image_for_grid(doc, &colours, (height, width, columns))
+            }
+        };
+
+        let data = match self.format {
+            OutputFormat::Svg => format!("{}", doc).into_bytes(),
+            OutputFormat::Png => {
+                let mut data = vec![];
+                rasterise(&doc)?.write_to_png(&mut data)?;
+
+                data
+            }
+            OutputFormat::Webp => encode_webp(rasterise(&doc)?)?,
         };
 
-        let opt = resvg::Options::default();
-        let svg = resvg::parse_doc_from_data(&format!("{}", doc), &opt)?;
-        let surface = resvg::render_cairo::render_to_image(&svg, &opt)?;
+        let image = ColourListImage {
+            name: format!("{}.{}", id.into(), self.format.extension()),
+            format: self.format,
+            data,
+        };
 
-        let mut path = PathBuf::new();
-        path.push("colour-images/");
-        path.push(id.into());
-        path.set_extension("png");
+        if let Some(ref dir) = self.debug_dir {
+            File::create(dir.join(&image.name))?.write_all(&image.data)?;
+        }
 
-        let mut file = File::create(&path)?;
-        surface.write_to_png(&mut file)?;
+        Ok(image)
+    }
+}
+
+fn rasterise(doc: &Document) -> Result<ImageSurface, ColourBuilderError> {
+    let opt = resvg::Options::default();
+    let svg = resvg::parse_doc_from_data(&format!("{}", doc), &opt)?;
+
+    Ok(resvg::render_cairo::render_to_image(&svg, &opt)?)
+}
 
-        Ok(path)
+/// Cairo stores pixels as premultiplied native endian ARGB, webp wants plain RGBA.
+fn encode_webp(mut surface: ImageSurface) -> Result<Vec<u8>, ColourBuilderError> {
+    let width = surface.get_width() as usize;
+    let height = surface.get_height() as usize;
+    let stride = surface.get_stride() as usize;
+
+    let data = surface.get_data()?;
+    let mut rgba = Vec::with_capacity(width * height * 4);
+
+    for row in data.chunks(stride).take(height) {
+        for pixel in row[..width * 4].chunks(4) {
+            let argb = u32::from(pixel[0])
+                | u32::from(pixel[1]) << 8
+                | u32::from(pixel[2]) << 16
+                | u32::from(pixel[3]) << 24;
+            let argb = u32::from_le(argb);
+
+            let alpha = (argb >> 24) as u8;
+            let unpremultiply = |channel: u32| {
+                if alpha == 0 {
+                    0
+                } else {
+                    ((channel & 0xFF) * 255 / u32::from(alpha)).min(255) as u8
+                }
+            };
+
+            rgba.push(unpremultiply(argb >> 16));
+            rgba.push(unpremultiply(argb >> 8));
+            rgba.push(unpremultiply(argb));
+            rgba.push(alpha);
+        }
     }
+
+    let encoded = WebpEncoder::from_rgba(&rgba, width as u32, height as u32).encode_lossless();
+
+    Ok(encoded.to_vec())
 }
@@ -0,0 +1,106 @@
+//! Fuzzy matching for colour names.
+//! Names are compared without case or punctuation, and exact, prefix and substring matches
+//! always beat a misspelling. The edits a misspelling can have scale with the length of the name,
+//! so "red" can't turn into every other three letter name.
+use edit_distance::edit_distance;
+
+/// A guild's tolerance is how many edits a name this long can be off by.
+/// Shorter names get fewer edits and longer names get more.
+pub const TOLERANCE_SCALE_LENGTH: usize = 6;
+
+/// Searches shorter than this don't count as the start or part of a name, they'd match too much.
+pub const MIN_PREFIX_LENGTH: usize = 2;
+pub const MIN_SUBSTRING_LENGTH: usize = 3;
+
+/// How a search matched a name, better matches sort first.
+/// The number on each kind is how many characters or edits the name was off by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NameMatch {
+    Exact,
+    Prefix(usize),
+    Substring(usize),
+    Fuzzy(usize),
+}
+
+/// Lowercases the name and drops anything that isn't a letter or number, so "Light-Blue" is "lightblue".
+pub fn normalise(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// How many edits a name of this many characters can be off by.
+pub fn allowed_edits(length: usize, tolerance: usize) -> usize {
+    length
+        .saturating_mul(tolerance)
+        .saturating_add(TOLERANCE_SCALE_LENGTH - 1)
+        / TOLERANCE_SCALE_LENGTH
+}
+
+/// Matches a search against a single name, `None` if the two are too different.
+pub fn match_name(search: &str, name: &str, tolerance: usize) -> Option<NameMatch> {
+    let search = normalise(search);
+    let name = normalise(name);
+
+    if search.is_empty() || name.is_empty() {
+        return None;
+    }
+
+    let search_length = search.chars().count();
+    let name_length = name.chars().count();
+    let extra = name_length.saturating_sub(search_length);
+
+    if search == name {
+        Some(NameMatch::Exact)
+    } else if search_length >= MIN_PREFIX_LENGTH && name.starts_with(&search) {
+        Some(NameMatch::Prefix(extra))
+    } else if search_length >= MIN_SUBSTRING_LENGTH && name.contains(&search) {
+        Some(NameMatch::Substring(extra))
+    } else {
+        let distance = edit_distance(&search, &name);
+
+        if distance <= allowed_edits(name_length, tolerance) {
+            Some(NameMatch::Fuzzy(distance))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn case_and_punctuation_are_ignored() {
+        assert_eq!(match_name("light blue", "Light-Blue", 0), Some(NameMatch::Exact));
+        assert_eq!(match_name("GRAY", "gray!", 0), Some(NameMatch::Exact));
+    }
+
+    #[test]
+    fn prefixes_and_substrings_beat_misspellings() {
+        let prefix = match_name("sky", "Sky Blue", 2).unwrap();
+        let substring = match_name("blue", "Sky Blue", 2).unwrap();
+        let fuzzy = match_name("skye", "Sky", 2).unwrap();
+
+        assert_eq!(prefix, NameMatch::Prefix(4));
+        assert_eq!(substring, NameMatch::Substring(3));
+        assert_eq!(fuzzy, NameMatch::Fuzzy(1));
+        assert!(prefix < substring && substring < fuzzy);
+
+        assert!(match_name("b", "Blue", 0).is_none());
+    }
+
+    #[test]
+    fn tolerance_scales_with_name_length() {
+        assert_eq!(allowed_edits(3, 2), 1);
+        assert_eq!(allowed_edits(6, 2), 2);
+        assert_eq!(allowed_edits(18, 2), 6);
+        assert_eq!(allowed_edits(18, usize::max_value()), usize::max_value() / TOLERANCE_SCALE_LENGTH);
+
+        assert!(match_name("rad", "red", 2).is_some());
+        assert!(match_name("bad", "red", 2).is_none());
+        assert!(match_name("mediumaquamarene", "Medium Aquamarine", 2).is_some());
+    }
+}
@@ -1,4 +1,7 @@
+pub mod distance;
 pub mod images;
+pub mod matching;
 pub mod models;
 pub use self::models::ParsedColour;
 pub mod names;
+pub mod parsing;
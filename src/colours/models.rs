@@ -1,5 +1,6 @@
+use colours::distance::{self, CieLab, DistanceMetric};
 use colours::names;
-use read_color::rgb;
+use colours::parsing;
 use serenity::utils::Colour;
 use std::cmp::Ordering;
 
@@ -13,12 +14,22 @@ use std::f64;
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
 pub enum ColourParseError {
     InvalidFormat,
+    InvalidHex,
+    InvalidRgb,
+    InvalidHsl,
+    InvalidHsv,
+    UnknownName,
 }
 
 impl ColourParseError {
     pub fn __description(&self) -> &str {
         match *self {
-            ColourParseError::InvalidFormat => "Invalid format given.",
+            ColourParseError::InvalidFormat => "Invalid format given. Use a hex code like #ff0000, rgb(255, 0, 0), hsl(0, 100%, 50%), hsv(0, 100%, 100%) or a colour name.",
+            ColourParseError::InvalidHex => "Invalid hex code given. Hex codes need 3 or 6 digits, like #f00 or #ff0000.",
+            ColourParseError::InvalidRgb => "Invalid rgb colour given. Use three values from 0 to 255, like rgb(255, 0, 0).",
+            ColourParseError::InvalidHsl => "Invalid hsl colour given. Use a hue from 0 to 360 then two percentages, like hsl(120, 50%, 50%).",
+            ColourParseError::InvalidHsv => "Invalid hsv colour given. Use a hue from 0 to 360 then two percentages, like hsv(120, 50%, 50%).",
+            ColourParseError::UnknownName => "Not a hex code, and no colour with that name exists. Try a hex code like #ff0000 or a css colour name like rebeccapurple.",
         }
     }
 }
@@ -36,6 +47,14 @@ impl Error for ColourParseError {
 }
 
 /// HSL colour represntations.
+/// Colours below this saturation are put in the greyscale group when sorting by hue bands.
+pub const GREYSCALE_SATURATION: f64 = 0.1;
+/// Colours outside these lightnesses look black or white no matter their hue.
+pub const GREYSCALE_DARKNESS: f64 = 0.08;
+pub const GREYSCALE_LIGHTNESS: f64 = 0.95;
+/// Width in degrees of each band when sorting by hue bands.
+pub const HUE_BAND_WIDTH: f64 = 30.0;
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct HSLColour<'a> {
     pub h: f64,
@@ -47,6 +66,20 @@ pub struct HSLColour<'a> {
 }
 
 impl<'a> HSLColour<'a> {
+    pub fn with_cmp(self, cmp: HSLCmpType) -> Self {
+        HSLColour { cmp, ..self }
+    }
+
+    /// Greys, near blacks and near whites have no hue worth sorting by.
+    pub fn is_greyscale(&self) -> bool {
+        self.s < GREYSCALE_SATURATION || self.l < GREYSCALE_DARKNESS || self.l > GREYSCALE_LIGHTNESS
+    }
+
+    /// Which of the `HUE_BAND_WIDTH` degree wide bands the hue falls into.
+    pub fn hue_band(&self) -> u8 {
+        (self.h / HUE_BAND_WIDTH) as u8
+    }
+
     pub fn to_parsed(&self) -> ParsedColour<'a> {
         let (r, g, b) = self.hsl_struct.to_rgb();
 
@@ -68,8 +101,21 @@ impl<'a> PartialOrd for HSLColour<'a> {
 }
 impl<'a> Ord for HSLColour<'a> {
     fn cmp(&self, other: &Self) -> Ordering {
+        let compare = |a: f64, b: f64| a.partial_cmp(&b).unwrap_or(Ordering::Less);
+
         match self.cmp {
-            HSLCmpType::Hue => self.h.partial_cmp(&other.h).unwrap_or(Ordering::Less),
+            HSLCmpType::Hue => compare(self.h, other.h),
+            HSLCmpType::Lightness => compare(self.l, other.l).then_with(|| compare(self.h, other.h)),
+            HSLCmpType::Saturation => compare(self.s, other.s).then_with(|| compare(self.h, other.h)),
+            HSLCmpType::HueBands => match (self.is_greyscale(), other.is_greyscale()) {
+                // greys with a faint hue still belong with the other greys, dark to light.
+                (true, true) => compare(self.l, other.l),
+                (false, false) => self
+                    .hue_band()
+                    .cmp(&other.hue_band())
+                    .then_with(|| compare(self.l, other.l)),
+                (own, other) => own.cmp(&other),
+            },
         }
     }
 }
@@ -89,6 +135,10 @@ impl<'a, 'b> From<&'b ParsedColour<'a>> for HSLColour<'a> {
 #[derive(Clone, Debug, PartialEq, Copy)]
 pub enum HSLCmpType {
     Hue,
+    Lightness,
+    Saturation,
+    /// Groups colours into hue bands, each sorted by lightness, with greyscale colours last.
+    HueBands,
 }
 
 /// Parsed colour methods.
@@ -101,33 +151,102 @@ pub struct ParsedColour<'a> {
     pub g: u8,
 }
 
-#[derive(Clone, PartialEq, Debug, Copy)]
+#[derive(Clone, PartialEq, Debug, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SortMethod {
+    #[serde(rename = "hsl")]
     HSL,
+    Lightness,
+    Saturation,
+    Name,
+    /// Oldest colour first. Needs the role ids, so only the list builder can sort by it.
+    DateAdded,
+    /// Most worn colour first. Needs the guild members, so only the list builder can sort by it.
+    UsageCount,
+    HueBands,
+}
+
+pub const SORT_METHOD_NAMES: [&str; 7] = [
+    "hsl",
+    "lightness",
+    "saturation",
+    "name",
+    "date_added",
+    "usage_count",
+    "hue_bands",
+];
+
+impl SortMethod {
+    /// The HSL comparison used for the method, if it sorts on the colour alone.
+    pub fn hsl_cmp_type(self) -> Option<HSLCmpType> {
+        match self {
+            SortMethod::HSL => Some(HSLCmpType::Hue),
+            SortMethod::Lightness => Some(HSLCmpType::Lightness),
+            SortMethod::Saturation => Some(HSLCmpType::Saturation),
+            SortMethod::HueBands => Some(HSLCmpType::HueBands),
+            SortMethod::Name | SortMethod::DateAdded | SortMethod::UsageCount => None,
+        }
+    }
+}
+
+impl Display for SortMethod {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+        let name = match *self {
+            SortMethod::HSL => SORT_METHOD_NAMES[0],
+            SortMethod::Lightness => SORT_METHOD_NAMES[1],
+            SortMethod::Saturation => SORT_METHOD_NAMES[2],
+            SortMethod::Name => SORT_METHOD_NAMES[3],
+            SortMethod::DateAdded => SORT_METHOD_NAMES[4],
+            SortMethod::UsageCount => SORT_METHOD_NAMES[5],
+            SortMethod::HueBands => SORT_METHOD_NAMES[6],
+        };
+
+        name.fmt(f)
+    }
+}
+
+impl FromStr for SortMethod {
+    type Err = String;
+
+    fn from_str(method: &str) -> Result<Self, Self::Err> {
+        match method.trim().to_lowercase().replace(' ', "_").as_str() {
+            "hsl" | "hue" => Ok(SortMethod::HSL),
+            "lightness" | "light" => Ok(SortMethod::Lightness),
+            "saturation" => Ok(SortMethod::Saturation),
+            "name" | "alphabetical" => Ok(SortMethod::Name),
+            "date_added" | "date" | "age" => Ok(SortMethod::DateAdded),
+            "usage_count" | "usage" | "popularity" => Ok(SortMethod::UsageCount),
+            "hue_bands" | "bands" => Ok(SortMethod::HueBands),
+            other => Err(format!(
+                "\"{}\" is not a sort method. Valid methods are: {}",
+                other,
+                SORT_METHOD_NAMES.join(", ")
+            )),
+        }
+    }
 }
 
 impl<'a> ParsedColour<'a> {
+    /// Sorts colours by the given method.
+    /// `DateAdded` and `UsageCount` need more than the colour, so the order is left as is for those.
     pub fn sort_list<T: Into<Self> + Clone>(colours: &[T], method: SortMethod) -> Vec<Self> {
-        let colours: Vec<Self> = colours.iter().cloned().map(T::into).collect();
-
-        match method {
-            SortMethod::HSL => {
-                let mut hsl_list: Vec<HSLColour> = colours.iter().map(HSLColour::from).collect();
+        let mut colours: Vec<Self> = colours.iter().cloned().map(T::into).collect();
 
-                hsl_list.sort_unstable();
+        colours.sort_by(|a, b| a.compare(b, method));
 
-                hsl_list.iter().map(|z| z.to_parsed()).collect()
-            }
+        colours
+    }
 
-            // SortMethod::Distance => {
-            //     colours.sort_by(|a, b| {
-            //         a.compute_distance(&b)
-            //             .partial_cmp(&b.compute_distance(a))
-            //             .unwrap_or(Ordering::Less)
-            //     });
+    /// Compares two colours by the given method, see `sort_list`.
+    pub fn compare(&self, other: &Self, method: SortMethod) -> Ordering {
+        match (method, method.hsl_cmp_type()) {
+            (_, Some(cmp)) => self.to_hsl().with_cmp(cmp).cmp(&other.to_hsl().with_cmp(cmp)),
+            (SortMethod::Name, _) => {
+                let name = |colour: &Self| colour.name.map(str::to_lowercase);
 
-            //     colours
-            // }
+                name(self).cmp(&name(other))
+            }
+            _ => Ordering::Equal,
         }
     }
 
@@ -135,13 +254,51 @@ impl<'a> ParsedColour<'a> {
         Colour::from_rgb(self.r, self.g, self.b)
     }
 
-    #[cfg_attr(rustfmt, rustfmt_skip)]
+    /// Distance to another colour using the default metric, see `compute_distance_with`.
     pub fn compute_distance(&self, other: &Self) -> f64 {
-        f64::abs(
-              (f64::from(other.r) - f64::from(self.r)).powf(2.0) 
-            + (f64::from(other.b) - f64::from(self.b)).powf(2.0)
-            + (f64::from(other.g) - f64::from(self.g)).powf(2.0),
-        )
+        self.compute_distance_with(other, DistanceMetric::default())
+    }
+
+    pub fn compute_distance_with(&self, other: &Self, metric: DistanceMetric) -> f64 {
+        match metric {
+            DistanceMetric::Rgb => distance::rgb_distance(self.to_tuple(), other.to_tuple()),
+            DistanceMetric::Ciede2000 => distance::ciede2000(&self.to_lab(), &other.to_lab()),
+        }
+    }
+
+    pub fn to_tuple(&self) -> (u8, u8, u8) {
+        (self.r, self.g, self.b)
+    }
+
+    pub fn to_lab(&self) -> CieLab {
+        CieLab::from_rgb(self.r, self.g, self.b)
+    }
+
+    /// WCAG relative luminance, 0 for black up to 1 for white.
+    pub fn relative_luminance(&self) -> f64 {
+        let channel = |c: u8| {
+            let c = f64::from(c) / 255.0;
+
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+
+        0.2126 * channel(self.r) + 0.7152 * channel(self.g) + 0.0722 * channel(self.b)
+    }
+
+    /// WCAG contrast ratio between two colours, from 1 for the same colour up to 21 for black on white.
+    pub fn contrast_ratio(&self, other: &Self) -> f64 {
+        let own = self.relative_luminance() + 0.05;
+        let other = other.relative_luminance() + 0.05;
+
+        if own > other {
+            own / other
+        } else {
+            other / own
+        }
     }
 
     pub fn to_hex(&self) -> u64 {
@@ -149,15 +306,26 @@ impl<'a> ParsedColour<'a> {
     }
 
     pub fn find_nearest(&self, colours: &[Self]) -> Option<Self> {
+        self.find_nearest_with(colours, DistanceMetric::default())
+    }
+
+    pub fn find_nearest_with(&self, colours: &[Self], metric: DistanceMetric) -> Option<Self> {
         let mut expected = None;
         let mut min_distance = f64::INFINITY;
 
+        // only convert this colour once, the list can be the few thousand colour names.
+        let own_lab = self.to_lab();
+
         for colour in colours {
             if colour.to_hex() == self.to_hex() {
                 return Some(colour.clone());
             }
 
-            let distance = self.compute_distance(colour);
+            let distance = match metric {
+                DistanceMetric::Ciede2000 => distance::ciede2000(&own_lab, &colour.to_lab()),
+                DistanceMetric::Rgb => self.compute_distance_with(colour, metric),
+            };
+
             if distance < min_distance {
                 min_distance = distance;
                 expected = Some(colour.clone());
@@ -215,37 +383,7 @@ impl<'a> FromStr for ParsedColour<'a> {
     type Err = ColourParseError;
 
     fn from_str(colour: &str) -> Result<Self, Self::Err> {
-        let colour = colour.replace("#", "");
-        let mut chars = colour.chars();
-
-        let range = if colour.len() == 3 {
-            let mut extended_chars: Vec<char> = vec![];
-
-            for ch in chars {
-                let mut chs = vec![ch; 2];
-                extended_chars.append(&mut chs);
-            }
-
-            let extended_chars = extended_chars.iter().collect::<String>();
-            let mut chars = extended_chars.chars();
-
-            rgb(&mut chars)
-        } else {
-            rgb(&mut chars)
-        };
-
-        let range = range.ok_or(ColourParseError::InvalidFormat)?;
-
-        let r = range[0];
-        let g = range[1];
-        let b = range[2];
-
-        Ok(ParsedColour {
-            r,
-            g,
-            b,
-            name: None,
-        })
+        parsing::parse_colour(colour)
     }
 }
 
@@ -276,6 +414,20 @@ mod test {
         assert_eq!(name, "Black")
     }
 
+    #[test]
+    pub fn nearest_name_follows_perceived_colour() {
+        // RGB distance puts this next to "Sea Nymph", CIEDE2000 picks the closer looking "Granny Smith".
+        let colour = ParsedColour::from_str("#7a9a9a").unwrap();
+
+        assert_eq!(colour.find_name().unwrap(), "Granny Smith");
+        assert_eq!(
+            colour
+                .find_nearest_with(&names::COLOUR_NAMES, DistanceMetric::Rgb)
+                .and_then(|c| c.name),
+            Some("Sea Nymph")
+        );
+    }
+
     #[test]
     pub fn malformed_colour_codes_fail() {
         let (result, entire) =  make_parser_many_case!(vec![
@@ -310,6 +462,65 @@ mod test {
     }
 
 
+    #[test]
+    pub fn other_colour_formats_pass() {
+        let cases = vec![
+            ("rgb(255, 0, 0)", "#FF0000"),
+            ("RGB(100%, 0%, 0%)", "#FF0000"),
+            ("rgba(0 255 0 / 0.5)", "#00FF00"),
+            ("0xff0000", "#FF0000"),
+            ("hsl(120, 100%, 50%)", "#00FF00"),
+            ("hsl(240deg, 100%, 50%)", "#0000FF"),
+            ("hsv(0, 100%, 100%)", "#FF0000"),
+            ("hsv(120, 50%, 50%)", "#408040"),
+            ("rebeccapurple", "#663399"),
+            ("Rebecca Purple", "#663399"),
+            ("navy blue", "#000080"),
+        ];
+
+        for (input, expected) in cases {
+            let colour = ParsedColour::from_str(input)
+                .unwrap_or_else(|e| panic!("{} failed to parse: {}", input, e));
+
+            assert_eq!(format!("{}", colour), expected, "{} parsed incorrectly", input);
+        }
+    }
+
+    #[test]
+    pub fn parse_errors_name_the_format() {
+        let cases = vec![
+            ("#fa", ColourParseError::InvalidHex),
+            ("rgb(256, 0, 0)", ColourParseError::InvalidRgb),
+            ("rgb(1, 2)", ColourParseError::InvalidRgb),
+            ("hsl(120, 150%, 50%)", ColourParseError::InvalidHsl),
+            ("hsv(red, 1, 1)", ColourParseError::InvalidHsv),
+            ("cmyk(0, 0, 0, 0)", ColourParseError::InvalidFormat),
+            ("not a colour", ColourParseError::UnknownName),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(ParsedColour::from_str(input), Err(expected), "{}", input);
+        }
+    }
+
+    #[test]
+    pub fn only_unmistakable_codes_are_colour_codes() {
+        assert_eq!(
+            parsing::parse_colour_code("#f00").map(|c| c.map(|c| c.to_string())),
+            Some(Ok("#FF0000".to_string()))
+        );
+        assert!(parsing::parse_colour_code("rgb(0, 255, 0)").is_some());
+        assert_eq!(
+            parsing::parse_colour_code("#ff"),
+            Some(Err(ColourParseError::InvalidHex))
+        );
+
+        // these parse as colours, but could just as well be a colour's name.
+        assert!(parsing::parse_colour_code("bad").is_none());
+        assert!(parsing::parse_colour_code("red").is_none());
+        assert!(parsing::parse_colour_code("hsl(0, 100%, 50%)").is_none());
+    }
+
     #[test]
     pub fn colours_format_properly() {
         let colour = ParsedColour {
@@ -380,4 +591,82 @@ mod test {
         assert!(correctness, "There was a disparity between an already sorted set and the sorted output! pre-sorted: {:?}, code-sorted: {:?}", colours, sorted_colours)
                 
     }
+
+    fn sorted_hex(codes: &[&str], method: SortMethod) -> Vec<String> {
+        let colours = codes
+            .iter()
+            .map(|x| ParsedColour::from_str(x))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        ParsedColour::sort_list(&colours, method)
+            .iter()
+            .map(|c| c.to_string())
+            .collect()
+    }
+
+    #[test]
+    pub fn lightness_and_saturation_sorts_are_correct() {
+        assert_eq!(
+            sorted_hex(&["#fff", "#000", "#808080"], SortMethod::Lightness),
+            vec!["#000000", "#808080", "#FFFFFF"]
+        );
+
+        assert_eq!(
+            sorted_hex(&["#f00", "#808080", "#bf4040"], SortMethod::Saturation),
+            vec!["#808080", "#BF4040", "#FF0000"]
+        );
+    }
+
+    #[test]
+    pub fn name_sort_ignores_case() {
+        let colours = vec![
+            ParsedColour { name: Some("red"), ..ParsedColour::from_str("#f00").unwrap() },
+            ParsedColour { name: Some("Blue"), ..ParsedColour::from_str("#00f").unwrap() },
+            ParsedColour { name: Some("green"), ..ParsedColour::from_str("#0f0").unwrap() },
+        ];
+
+        let names = ParsedColour::sort_list(&colours, SortMethod::Name)
+            .iter()
+            .map(|c| c.name.unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, vec!["Blue", "green", "red"]);
+    }
+
+    #[test]
+    pub fn hue_bands_group_greys_last() {
+        assert_eq!(
+            sorted_hex(
+                &["#111", "#800000", "#f00", "#fff", "#7a7f8a", "#0000ff", "#010101"],
+                SortMethod::HueBands
+            ),
+            vec!["#800000", "#FF0000", "#0000FF", "#010101", "#111111", "#7A7F8A", "#FFFFFF"]
+        );
+    }
+
+    #[test]
+    pub fn contrast_ratios_match_wcag() {
+        let ratio = |a: &str, b: &str| {
+            ParsedColour::from_str(a)
+                .unwrap()
+                .contrast_ratio(&ParsedColour::from_str(b).unwrap())
+        };
+
+        assert!((ratio("#000", "#fff") - 21.0).abs() < 0.01);
+        assert!((ratio("#fff", "#000") - 21.0).abs() < 0.01);
+        assert!((ratio("#777", "#fff") - 4.48).abs() < 0.01);
+        assert_eq!(ratio("#36393e", "#36393e"), 1.0);
+    }
+
+    #[test]
+    pub fn sort_methods_round_trip() {
+        for name in SORT_METHOD_NAMES.iter() {
+            let method = SortMethod::from_str(name).unwrap();
+
+            assert_eq!(&method.to_string(), name);
+        }
+
+        assert!(SortMethod::from_str("rainbow").is_err());
+    }
 }
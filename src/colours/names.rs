@@ -9399,3 +9399,895 @@ pub const COLOUR_NAMES: [ParsedColour; 1566] = [
         name: Some("White"),
     },
 ];
+
+// from: https://www.w3.org/TR/css-color-4/#named-colors
+pub const CSS_COLOUR_NAMES: [ParsedColour; 148] = [
+    ParsedColour {
+        r: 240,
+        g: 248,
+        b: 255,
+        name: Some("aliceblue"),
+    },
+    ParsedColour {
+        r: 250,
+        g: 235,
+        b: 215,
+        name: Some("antiquewhite"),
+    },
+    ParsedColour {
+        r: 0,
+        g: 255,
+        b: 255,
+        name: Some("aqua"),
+    },
+    ParsedColour {
+        r: 127,
+        g: 255,
+        b: 212,
+        name: Some("aquamarine"),
+    },
+    ParsedColour {
+        r: 240,
+        g: 255,
+        b: 255,
+        name: Some("azure"),
+    },
+    ParsedColour {
+        r: 245,
+        g: 245,
+        b: 220,
+        name: Some("beige"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 228,
+        b: 196,
+        name: Some("bisque"),
+    },
+    ParsedColour {
+        r: 0,
+        g: 0,
+        b: 0,
+        name: Some("black"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 235,
+        b: 205,
+        name: Some("blanchedalmond"),
+    },
+    ParsedColour {
+        r: 0,
+        g: 0,
+        b: 255,
+        name: Some("blue"),
+    },
+    ParsedColour {
+        r: 138,
+        g: 43,
+        b: 226,
+        name: Some("blueviolet"),
+    },
+    ParsedColour {
+        r: 165,
+        g: 42,
+        b: 42,
+        name: Some("brown"),
+    },
+    ParsedColour {
+        r: 222,
+        g: 184,
+        b: 135,
+        name: Some("burlywood"),
+    },
+    ParsedColour {
+        r: 95,
+        g: 158,
+        b: 160,
+        name: Some("cadetblue"),
+    },
+    ParsedColour {
+        r: 127,
+        g: 255,
+        b: 0,
+        name: Some("chartreuse"),
+    },
+    ParsedColour {
+        r: 210,
+        g: 105,
+        b: 30,
+        name: Some("chocolate"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 127,
+        b: 80,
+        name: Some("coral"),
+    },
+    ParsedColour {
+        r: 100,
+        g: 149,
+        b: 237,
+        name: Some("cornflowerblue"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 248,
+        b: 220,
+        name: Some("cornsilk"),
+    },
+    ParsedColour {
+        r: 220,
+        g: 20,
+        b: 60,
+        name: Some("crimson"),
+    },
+    ParsedColour {
+        r: 0,
+        g: 255,
+        b: 255,
+        name: Some("cyan"),
+    },
+    ParsedColour {
+        r: 0,
+        g: 0,
+        b: 139,
+        name: Some("darkblue"),
+    },
+    ParsedColour {
+        r: 0,
+        g: 139,
+        b: 139,
+        name: Some("darkcyan"),
+    },
+    ParsedColour {
+        r: 184,
+        g: 134,
+        b: 11,
+        name: Some("darkgoldenrod"),
+    },
+    ParsedColour {
+        r: 169,
+        g: 169,
+        b: 169,
+        name: Some("darkgray"),
+    },
+    ParsedColour {
+        r: 0,
+        g: 100,
+        b: 0,
+        name: Some("darkgreen"),
+    },
+    ParsedColour {
+        r: 169,
+        g: 169,
+        b: 169,
+        name: Some("darkgrey"),
+    },
+    ParsedColour {
+        r: 189,
+        g: 183,
+        b: 107,
+        name: Some("darkkhaki"),
+    },
+    ParsedColour {
+        r: 139,
+        g: 0,
+        b: 139,
+        name: Some("darkmagenta"),
+    },
+    ParsedColour {
+        r: 85,
+        g: 107,
+        b: 47,
+        name: Some("darkolivegreen"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 140,
+        b: 0,
+        name: Some("darkorange"),
+    },
+    ParsedColour {
+        r: 153,
+        g: 50,
+        b: 204,
+        name: Some("darkorchid"),
+    },
+    ParsedColour {
+        r: 139,
+        g: 0,
+        b: 0,
+        name: Some("darkred"),
+    },
+    ParsedColour {
+        r: 233,
+        g: 150,
+        b: 122,
+        name: Some("darksalmon"),
+    },
+    ParsedColour {
+        r: 143,
+        g: 188,
+        b: 143,
+        name: Some("darkseagreen"),
+    },
+    ParsedColour {
+        r: 72,
+        g: 61,
+        b: 139,
+        name: Some("darkslateblue"),
+    },
+    ParsedColour {
+        r: 47,
+        g: 79,
+        b: 79,
+        name: Some("darkslategray"),
+    },
+    ParsedColour {
+        r: 47,
+        g: 79,
+        b: 79,
+        name: Some("darkslategrey"),
+    },
+    ParsedColour {
+        r: 0,
+        g: 206,
+        b: 209,
+        name: Some("darkturquoise"),
+    },
+    ParsedColour {
+        r: 148,
+        g: 0,
+        b: 211,
+        name: Some("darkviolet"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 20,
+        b: 147,
+        name: Some("deeppink"),
+    },
+    ParsedColour {
+        r: 0,
+        g: 191,
+        b: 255,
+        name: Some("deepskyblue"),
+    },
+    ParsedColour {
+        r: 105,
+        g: 105,
+        b: 105,
+        name: Some("dimgray"),
+    },
+    ParsedColour {
+        r: 105,
+        g: 105,
+        b: 105,
+        name: Some("dimgrey"),
+    },
+    ParsedColour {
+        r: 30,
+        g: 144,
+        b: 255,
+        name: Some("dodgerblue"),
+    },
+    ParsedColour {
+        r: 178,
+        g: 34,
+        b: 34,
+        name: Some("firebrick"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 250,
+        b: 240,
+        name: Some("floralwhite"),
+    },
+    ParsedColour {
+        r: 34,
+        g: 139,
+        b: 34,
+        name: Some("forestgreen"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 0,
+        b: 255,
+        name: Some("fuchsia"),
+    },
+    ParsedColour {
+        r: 220,
+        g: 220,
+        b: 220,
+        name: Some("gainsboro"),
+    },
+    ParsedColour {
+        r: 248,
+        g: 248,
+        b: 255,
+        name: Some("ghostwhite"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 215,
+        b: 0,
+        name: Some("gold"),
+    },
+    ParsedColour {
+        r: 218,
+        g: 165,
+        b: 32,
+        name: Some("goldenrod"),
+    },
+    ParsedColour {
+        r: 128,
+        g: 128,
+        b: 128,
+        name: Some("gray"),
+    },
+    ParsedColour {
+        r: 0,
+        g: 128,
+        b: 0,
+        name: Some("green"),
+    },
+    ParsedColour {
+        r: 173,
+        g: 255,
+        b: 47,
+        name: Some("greenyellow"),
+    },
+    ParsedColour {
+        r: 128,
+        g: 128,
+        b: 128,
+        name: Some("grey"),
+    },
+    ParsedColour {
+        r: 240,
+        g: 255,
+        b: 240,
+        name: Some("honeydew"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 105,
+        b: 180,
+        name: Some("hotpink"),
+    },
+    ParsedColour {
+        r: 205,
+        g: 92,
+        b: 92,
+        name: Some("indianred"),
+    },
+    ParsedColour {
+        r: 75,
+        g: 0,
+        b: 130,
+        name: Some("indigo"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 255,
+        b: 240,
+        name: Some("ivory"),
+    },
+    ParsedColour {
+        r: 240,
+        g: 230,
+        b: 140,
+        name: Some("khaki"),
+    },
+    ParsedColour {
+        r: 230,
+        g: 230,
+        b: 250,
+        name: Some("lavender"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 240,
+        b: 245,
+        name: Some("lavenderblush"),
+    },
+    ParsedColour {
+        r: 124,
+        g: 252,
+        b: 0,
+        name: Some("lawngreen"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 250,
+        b: 205,
+        name: Some("lemonchiffon"),
+    },
+    ParsedColour {
+        r: 173,
+        g: 216,
+        b: 230,
+        name: Some("lightblue"),
+    },
+    ParsedColour {
+        r: 240,
+        g: 128,
+        b: 128,
+        name: Some("lightcoral"),
+    },
+    ParsedColour {
+        r: 224,
+        g: 255,
+        b: 255,
+        name: Some("lightcyan"),
+    },
+    ParsedColour {
+        r: 250,
+        g: 250,
+        b: 210,
+        name: Some("lightgoldenrodyellow"),
+    },
+    ParsedColour {
+        r: 211,
+        g: 211,
+        b: 211,
+        name: Some("lightgray"),
+    },
+    ParsedColour {
+        r: 144,
+        g: 238,
+        b: 144,
+        name: Some("lightgreen"),
+    },
+    ParsedColour {
+        r: 211,
+        g: 211,
+        b: 211,
+        name: Some("lightgrey"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 182,
+        b: 193,
+        name: Some("lightpink"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 160,
+        b: 122,
+        name: Some("lightsalmon"),
+    },
+    ParsedColour {
+        r: 32,
+        g: 178,
+        b: 170,
+        name: Some("lightseagreen"),
+    },
+    ParsedColour {
+        r: 135,
+        g: 206,
+        b: 250,
+        name: Some("lightskyblue"),
+    },
+    ParsedColour {
+        r: 119,
+        g: 136,
+        b: 153,
+        name: Some("lightslategray"),
+    },
+    ParsedColour {
+        r: 119,
+        g: 136,
+        b: 153,
+        name: Some("lightslategrey"),
+    },
+    ParsedColour {
+        r: 176,
+        g: 196,
+        b: 222,
+        name: Some("lightsteelblue"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 255,
+        b: 224,
+        name: Some("lightyellow"),
+    },
+    ParsedColour {
+        r: 0,
+        g: 255,
+        b: 0,
+        name: Some("lime"),
+    },
+    ParsedColour {
+        r: 50,
+        g: 205,
+        b: 50,
+        name: Some("limegreen"),
+    },
+    ParsedColour {
+        r: 250,
+        g: 240,
+        b: 230,
+        name: Some("linen"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 0,
+        b: 255,
+        name: Some("magenta"),
+    },
+    ParsedColour {
+        r: 128,
+        g: 0,
+        b: 0,
+        name: Some("maroon"),
+    },
+    ParsedColour {
+        r: 102,
+        g: 205,
+        b: 170,
+        name: Some("mediumaquamarine"),
+    },
+    ParsedColour {
+        r: 0,
+        g: 0,
+        b: 205,
+        name: Some("mediumblue"),
+    },
+    ParsedColour {
+        r: 186,
+        g: 85,
+        b: 211,
+        name: Some("mediumorchid"),
+    },
+    ParsedColour {
+        r: 147,
+        g: 112,
+        b: 219,
+        name: Some("mediumpurple"),
+    },
+    ParsedColour {
+        r: 60,
+        g: 179,
+        b: 113,
+        name: Some("mediumseagreen"),
+    },
+    ParsedColour {
+        r: 123,
+        g: 104,
+        b: 238,
+        name: Some("mediumslateblue"),
+    },
+    ParsedColour {
+        r: 0,
+        g: 250,
+        b: 154,
+        name: Some("mediumspringgreen"),
+    },
+    ParsedColour {
+        r: 72,
+        g: 209,
+        b: 204,
+        name: Some("mediumturquoise"),
+    },
+    ParsedColour {
+        r: 199,
+        g: 21,
+        b: 133,
+        name: Some("mediumvioletred"),
+    },
+    ParsedColour {
+        r: 25,
+        g: 25,
+        b: 112,
+        name: Some("midnightblue"),
+    },
+    ParsedColour {
+        r: 245,
+        g: 255,
+        b: 250,
+        name: Some("mintcream"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 228,
+        b: 225,
+        name: Some("mistyrose"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 228,
+        b: 181,
+        name: Some("moccasin"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 222,
+        b: 173,
+        name: Some("navajowhite"),
+    },
+    ParsedColour {
+        r: 0,
+        g: 0,
+        b: 128,
+        name: Some("navy"),
+    },
+    ParsedColour {
+        r: 253,
+        g: 245,
+        b: 230,
+        name: Some("oldlace"),
+    },
+    ParsedColour {
+        r: 128,
+        g: 128,
+        b: 0,
+        name: Some("olive"),
+    },
+    ParsedColour {
+        r: 107,
+        g: 142,
+        b: 35,
+        name: Some("olivedrab"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 165,
+        b: 0,
+        name: Some("orange"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 69,
+        b: 0,
+        name: Some("orangered"),
+    },
+    ParsedColour {
+        r: 218,
+        g: 112,
+        b: 214,
+        name: Some("orchid"),
+    },
+    ParsedColour {
+        r: 238,
+        g: 232,
+        b: 170,
+        name: Some("palegoldenrod"),
+    },
+    ParsedColour {
+        r: 152,
+        g: 251,
+        b: 152,
+        name: Some("palegreen"),
+    },
+    ParsedColour {
+        r: 175,
+        g: 238,
+        b: 238,
+        name: Some("paleturquoise"),
+    },
+    ParsedColour {
+        r: 219,
+        g: 112,
+        b: 147,
+        name: Some("palevioletred"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 239,
+        b: 213,
+        name: Some("papayawhip"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 218,
+        b: 185,
+        name: Some("peachpuff"),
+    },
+    ParsedColour {
+        r: 205,
+        g: 133,
+        b: 63,
+        name: Some("peru"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 192,
+        b: 203,
+        name: Some("pink"),
+    },
+    ParsedColour {
+        r: 221,
+        g: 160,
+        b: 221,
+        name: Some("plum"),
+    },
+    ParsedColour {
+        r: 176,
+        g: 224,
+        b: 230,
+        name: Some("powderblue"),
+    },
+    ParsedColour {
+        r: 128,
+        g: 0,
+        b: 128,
+        name: Some("purple"),
+    },
+    ParsedColour {
+        r: 102,
+        g: 51,
+        b: 153,
+        name: Some("rebeccapurple"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 0,
+        b: 0,
+        name: Some("red"),
+    },
+    ParsedColour {
+        r: 188,
+        g: 143,
+        b: 143,
+        name: Some("rosybrown"),
+    },
+    ParsedColour {
+        r: 65,
+        g: 105,
+        b: 225,
+        name: Some("royalblue"),
+    },
+    ParsedColour {
+        r: 139,
+        g: 69,
+        b: 19,
+        name: Some("saddlebrown"),
+    },
+    ParsedColour {
+        r: 250,
+        g: 128,
+        b: 114,
+        name: Some("salmon"),
+    },
+    ParsedColour {
+        r: 244,
+        g: 164,
+        b: 96,
+        name: Some("sandybrown"),
+    },
+    ParsedColour {
+        r: 46,
+        g: 139,
+        b: 87,
+        name: Some("seagreen"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 245,
+        b: 238,
+        name: Some("seashell"),
+    },
+    ParsedColour {
+        r: 160,
+        g: 82,
+        b: 45,
+        name: Some("sienna"),
+    },
+    ParsedColour {
+        r: 192,
+        g: 192,
+        b: 192,
+        name: Some("silver"),
+    },
+    ParsedColour {
+        r: 135,
+        g: 206,
+        b: 235,
+        name: Some("skyblue"),
+    },
+    ParsedColour {
+        r: 106,
+        g: 90,
+        b: 205,
+        name: Some("slateblue"),
+    },
+    ParsedColour {
+        r: 112,
+        g: 128,
+        b: 144,
+        name: Some("slategray"),
+    },
+    ParsedColour {
+        r: 112,
+        g: 128,
+        b: 144,
+        name: Some("slategrey"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 250,
+        b: 250,
+        name: Some("snow"),
+    },
+    ParsedColour {
+        r: 0,
+        g: 255,
+        b: 127,
+        name: Some("springgreen"),
+    },
+    ParsedColour {
+        r: 70,
+        g: 130,
+        b: 180,
+        name: Some("steelblue"),
+    },
+    ParsedColour {
+        r: 210,
+        g: 180,
+        b: 140,
+        name: Some("tan"),
+    },
+    ParsedColour {
+        r: 0,
+        g: 128,
+        b: 128,
+        name: Some("teal"),
+    },
+    ParsedColour {
+        r: 216,
+        g: 191,
+        b: 216,
+        name: Some("thistle"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 99,
+        b: 71,
+        name: Some("tomato"),
+    },
+    ParsedColour {
+        r: 64,
+        g: 224,
+        b: 208,
+        name: Some("turquoise"),
+    },
+    ParsedColour {
+        r: 238,
+        g: 130,
+        b: 238,
+        name: Some("violet"),
+    },
+    ParsedColour {
+        r: 245,
+        g: 222,
+        b: 179,
+        name: Some("wheat"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 255,
+        b: 255,
+        name: Some("white"),
+    },
+    ParsedColour {
+        r: 245,
+        g: 245,
+        b: 245,
+        name: Some("whitesmoke"),
+    },
+    ParsedColour {
+        r: 255,
+        g: 255,
+        b: 0,
+        name: Some("yellow"),
+    },
+    ParsedColour {
+        r: 154,
+        g: 205,
+        b: 50,
+        name: Some("yellowgreen"),
+    },
+];
@@ -0,0 +1,222 @@
+//! Parsers for the different ways a colour can be written.
+//! Supports hex codes (`#f00`, `ff0000`, `0xff0000`), `rgb()`, `hsl()`, `hsv()` and colour names.
+use colours::models::{ColourParseError, ParsedColour};
+use colours::names;
+
+use hsl::HSL;
+use read_color::rgb;
+
+/// Parses any of the supported colour formats.
+pub fn parse_colour<'a>(colour: &str) -> Result<ParsedColour<'a>, ColourParseError> {
+    let colour = colour.trim().to_lowercase();
+
+    if let Some(args) = function_args(&colour, &["rgba", "rgb"]) {
+        return parse_rgb(args);
+    }
+
+    if let Some(args) = function_args(&colour, &["hsla", "hsl"]) {
+        return parse_hsl(args);
+    }
+
+    if let Some(args) = function_args(&colour, &["hsva", "hsv", "hsb"]) {
+        return parse_hsv(args);
+    }
+
+    if colour.contains('(') {
+        return Err(ColourParseError::InvalidFormat);
+    }
+
+    if colour.starts_with('#') {
+        return parse_hex(&colour[1..]);
+    }
+
+    if colour.starts_with("0x") {
+        return parse_hex(&colour[2..]);
+    }
+
+    parse_hex(&colour).or_else(|_| parse_name(&colour))
+}
+
+/// Parses hex codes starting with `#` or `0x`, and `rgb()`, the formats nobody would name a colour.
+/// Gives back `None` for anything else, so names can be looked up as usual.
+pub fn parse_colour_code<'a>(colour: &str) -> Option<Result<ParsedColour<'a>, ColourParseError>> {
+    let colour = colour.trim().to_lowercase();
+
+    if let Some(args) = function_args(&colour, &["rgba", "rgb"]) {
+        return Some(parse_rgb(args));
+    }
+
+    if colour.starts_with('#') {
+        return Some(parse_hex(&colour[1..]));
+    }
+
+    if colour.starts_with("0x") {
+        return Some(parse_hex(&colour[2..]));
+    }
+
+    None
+}
+
+/// Gets the comma or space separated arguments out of something like `rgb(255, 0, 0)`.
+fn function_args<'s>(colour: &'s str, names: &[&str]) -> Option<Vec<&'s str>> {
+    let open = colour.find('(')?;
+
+    if !names.contains(&colour[..open].trim()) || !colour.ends_with(')') {
+        return None;
+    }
+
+    Some(
+        colour[open + 1..colour.len() - 1]
+            .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+            .filter(|arg| !arg.is_empty())
+            .collect(),
+    )
+}
+
+/// Reads a number that may be a percentage, percentages are scaled to `max`.
+fn parse_number(arg: &str, max: f64) -> Option<f64> {
+    let value = if arg.ends_with('%') {
+        arg.trim_right_matches('%').parse::<f64>().ok()? / 100.0 * max
+    } else {
+        arg.parse::<f64>().ok()?
+    };
+
+    if value.is_finite() && value >= 0.0 && value <= max {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+/// Reads a hue in degrees, wrapping it around into 0..360.
+fn parse_hue(arg: &str) -> Option<f64> {
+    let hue = arg.trim_right_matches("deg").parse::<f64>().ok()?;
+
+    if hue.is_finite() {
+        Some(((hue % 360.0) + 360.0) % 360.0)
+    } else {
+        None
+    }
+}
+
+/// Reads a saturation, lightness or value. `50%` and `50` both mean half.
+fn parse_fraction(arg: &str) -> Option<f64> {
+    parse_number(arg.trim_right_matches('%'), 100.0).map(|value| value / 100.0)
+}
+
+/// Alpha is allowed in the input so css can be pasted in, but roles can't be transparent so it's ignored.
+fn has_valid_length(args: &[&str]) -> bool {
+    args.len() == 3 || args.len() == 4
+}
+
+fn parse_hex<'a>(hex: &str) -> Result<ParsedColour<'a>, ColourParseError> {
+    let extended = match hex.len() {
+        3 => hex.chars().flat_map(|ch| vec![ch; 2]).collect::<String>(),
+        6 => hex.to_string(),
+        _ => return Err(ColourParseError::InvalidHex),
+    };
+
+    let range = rgb(&mut extended.chars()).ok_or(ColourParseError::InvalidHex)?;
+
+    Ok(ParsedColour {
+        r: range[0],
+        g: range[1],
+        b: range[2],
+        name: None,
+    })
+}
+
+fn parse_rgb<'a>(args: Vec<&str>) -> Result<ParsedColour<'a>, ColourParseError> {
+    if !has_valid_length(&args) {
+        return Err(ColourParseError::InvalidRgb);
+    }
+
+    let values = args[..3]
+        .iter()
+        .map(|arg| parse_number(arg, 255.0).map(|v| v.round() as u8))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or(ColourParseError::InvalidRgb)?;
+
+    Ok(ParsedColour {
+        r: values[0],
+        g: values[1],
+        b: values[2],
+        name: None,
+    })
+}
+
+fn parse_hsl<'a>(args: Vec<&str>) -> Result<ParsedColour<'a>, ColourParseError> {
+    if !has_valid_length(&args) {
+        return Err(ColourParseError::InvalidHsl);
+    }
+
+    let h = parse_hue(args[0]).ok_or(ColourParseError::InvalidHsl)?;
+    let s = parse_fraction(args[1]).ok_or(ColourParseError::InvalidHsl)?;
+    let l = parse_fraction(args[2]).ok_or(ColourParseError::InvalidHsl)?;
+
+    let (r, g, b) = HSL { h, s, l }.to_rgb();
+
+    Ok(ParsedColour {
+        r,
+        g,
+        b,
+        name: None,
+    })
+}
+
+fn parse_hsv<'a>(args: Vec<&str>) -> Result<ParsedColour<'a>, ColourParseError> {
+    if !has_valid_length(&args) {
+        return Err(ColourParseError::InvalidHsv);
+    }
+
+    let h = parse_hue(args[0]).ok_or(ColourParseError::InvalidHsv)?;
+    let s = parse_fraction(args[1]).ok_or(ColourParseError::InvalidHsv)?;
+    let v = parse_fraction(args[2]).ok_or(ColourParseError::InvalidHsv)?;
+
+    let chroma = v * s;
+    let sector = h / 60.0;
+    let x = chroma * (1.0 - ((sector % 2.0) - 1.0).abs());
+
+    let (r, g, b) = match sector as u8 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    let m = v - chroma;
+    let to_byte = |channel: f64| ((channel + m) * 255.0).round() as u8;
+
+    Ok(ParsedColour {
+        r: to_byte(r),
+        g: to_byte(g),
+        b: to_byte(b),
+        name: None,
+    })
+}
+
+/// Names are compared without case, spaces, dashes or underscores, so "Navy Blue" matches "navyblue".
+fn normalise_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Looks a name up in the css colours first, then the larger list of colour names.
+fn parse_name<'a>(name: &str) -> Result<ParsedColour<'a>, ColourParseError> {
+    let name = normalise_name(name);
+
+    if name.is_empty() {
+        return Err(ColourParseError::UnknownName);
+    }
+
+    names::CSS_COLOUR_NAMES
+        .iter()
+        .chain(names::COLOUR_NAMES.iter())
+        .find(|colour| colour.name.map(normalise_name).as_ref() == Some(&name))
+        .cloned()
+        .ok_or(ColourParseError::UnknownName)
+}
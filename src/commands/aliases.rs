@@ -0,0 +1,93 @@
+use actions;
+use utils;
+
+use serenity::framework::standard::{Args, CommandError, CreateCommand};
+use serenity::model::permissions::Permissions;
+use serenity::model::prelude::Message;
+use serenity::prelude::Context;
+
+/// Adds, removes or lists the other names a colour can be found by.
+pub fn alias(cmd: CreateCommand) -> CreateCommand {
+    cmd.batch_known_as(&["aliases", "nickname"])
+        .desc("Manages other names a colour can be found by, so \"grey\" can find \"Gray\".")
+        .help_available(true)
+        .required_permissions(Permissions::MANAGE_ROLES)
+        .usage("<add | remove | list> <colour name> [alias]")
+        .example("add \"Rose\" pink")
+        .min_args(2)
+        .exec(alias_exec)
+}
+
+pub fn alias_exec(_: &mut Context, msg: &Message, mut args: Args) -> Result<(), CommandError> {
+    let connection = utils::get_connection_or_panic();
+
+    let guild_id = msg
+        .guild_id
+        .ok_or_else(|| CommandError("This command only works on a guild.".to_string()))?;
+    let guild_record = actions::guilds::convert_guild_to_record(guild_id, &connection)
+        .ok_or_else(|| CommandError("No guild was found in the database. This means you have not created a colour on this server yet.".to_string()))?;
+
+    let action = args.single::<String>()?.to_lowercase();
+    let colour_name = args.single_quoted::<String>()?;
+    let alias = args.rest().trim().to_string();
+
+    let colour =
+        utils::pick_colour_from_name(msg, &colour_name, &guild_record, &connection, true)?
+            .ok_or_else(|| {
+                CommandError(format!(
+                    "The colour {} could not be found. Check your spelling!",
+                    colour_name
+                ))
+            })?;
+
+    let reply = match (action.as_str(), alias.is_empty()) {
+        ("add", false) => {
+            let added = actions::aliases::add_alias(&colour, &alias, &guild_record, &connection)
+                .map_err(|e| CommandError(e.to_string()))?;
+
+            format!("**{}** can now also be found as `{}`.", colour.name, added.name)
+        }
+        ("remove", false) => {
+            let removed = actions::aliases::remove_alias(&colour, &alias, &connection)
+                .map_err(|e| CommandError(e.to_string()))?;
+
+            format!("**{}** can no longer be found as `{}`.", colour.name, removed)
+        }
+        ("list", _) => {
+            let aliases = actions::aliases::find_for_colour(&colour, &connection);
+
+            if aliases.is_empty() {
+                format!("**{}** has no aliases.", colour.name)
+            } else {
+                format!(
+                    "**{}** can also be found as: {}",
+                    colour.name,
+                    aliases
+                        .iter()
+                        .map(|alias| format!("`{}`", alias))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }
+        }
+        ("add", true) | ("remove", true) => {
+            return Err(CommandError(format!(
+                "No alias was given to {}. Put it after the colour name.",
+                action
+            )))
+        }
+        (act, _) => {
+            return Err(CommandError(format!(
+                "No alias action exists for the input \"{}\". Please check the help.",
+                act
+            )))
+        }
+    };
+
+    let cleanup_delay = guild_record.settings().cleanup_delay;
+    let self_reply = msg.channel_id.send_message(|m| m.content(reply))?;
+
+    delay_delete!(self_reply; cleanup_delay);
+
+    Ok(())
+}
@@ -0,0 +1,215 @@
+use actions;
+use actions::cycles::CycleTarget;
+use utils;
+
+use diesel::pg::PgConnection;
+
+use serenity::framework::standard::{Args, CommandError, CreateCommand};
+use serenity::model::prelude::{Guild, Message};
+use serenity::prelude::Context;
+
+use db::models::Cycle;
+
+/// Starts, stops or shows the colour rotations running in the guild.
+pub fn cycle(cmd: CreateCommand) -> CreateCommand {
+    cmd.batch_known_as(&["cycles", "rotate"])
+        .desc("Rotates you, or a role if you can manage roles, through a set of colours on a schedule. Plain numbers are read as minutes.")
+        .help_available(true)
+        .usage("<start [@role] <interval> <colour>, <colour>, ... | stop [@role] | status>")
+        .example("start 1h Red, Orange, Yellow")
+        .min_args(1)
+        .exec(cycle_exec)
+}
+
+pub fn cycle_exec(_: &mut Context, msg: &Message, mut args: Args) -> Result<(), CommandError> {
+    let connection = utils::get_connection_or_panic();
+
+    let guild = utils::get_guild_result(msg)?;
+    let guild_id = guild.read().id;
+    let guild_record = actions::guilds::convert_guild_to_record(guild_id, &connection)
+        .ok_or_else(|| CommandError("No guild was found in the database. This means you have not created a colour on this server yet.".to_string()))?;
+
+    let action = args.single::<String>()?.to_lowercase();
+
+    let reply = match action.as_str() {
+        "start" => {
+            let first = args.single_n::<String>()?;
+
+            // the interval comes first unless a role is being cycled.
+            let (target, target_name) = if utils::parse_interval(&first).is_some() {
+                (CycleTarget::User(msg.author.id), "Your colour".to_string())
+            } else {
+                let guild = guild.read();
+                let role = utils::get_or_search_role_from_arg(&guild, &mut args)?;
+
+                check_can_manage(&guild, msg)?;
+
+                if actions::colours::find_from_role_id(role.id, &connection).is_some() {
+                    return Err(CommandError(format!(
+                        "{} is on the colour list, cycling it would change the colour everyone picked. Cycle a different role instead.",
+                        role.name
+                    )));
+                }
+
+                (CycleTarget::Role(role.id), format!("**{}**", role.name))
+            };
+
+            let interval = args.single::<String>()?;
+            let interval = utils::parse_interval(&interval).ok_or_else(|| {
+                CommandError(format!(
+                    "Couldn't read \"{}\" as an interval, try something like `30m` or `2h`.",
+                    interval
+                ))
+            })?;
+
+            // picking colours can wait on the user, so no guild lock is held from here on.
+            let colours = args
+                .rest()
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(|name| {
+                    utils::pick_colour_from_name(msg, name, &guild_record, &connection, true)?
+                        .ok_or_else(|| {
+                            CommandError(format!(
+                                "The colour {} could not be found. Check your spelling!",
+                                name
+                            ))
+                        })
+                }).collect::<Result<Vec<_>, _>>()?;
+
+            if let CycleTarget::User(user) = target {
+                let guild = guild.read();
+                let now = utils::unix_timestamp();
+
+                for colour in &colours {
+                    actions::requirements::check_member(colour, user, &guild, now, &connection)
+                        .map_err(|e| CommandError(e.to_string()))?;
+                }
+            }
+
+            actions::cycles::start_cycle(
+                &guild_record,
+                target,
+                &colours,
+                interval,
+                utils::unix_timestamp(),
+                &connection,
+            ).map_err(|e| CommandError(e.to_string()))?;
+
+            format!(
+                "{} will now cycle through {} every {}.",
+                target_name,
+                describe_colours(&colours.iter().map(|c| c.name.clone()).collect::<Vec<_>>()),
+                utils::format_interval(interval)
+            )
+        }
+        "stop" => {
+            let (target, target_name) = if args.rest().trim().is_empty() {
+                (CycleTarget::User(msg.author.id), "Your colour".to_string())
+            } else {
+                let guild = guild.read();
+                let role = utils::get_or_search_role_from_arg(&guild, &mut args)?;
+
+                check_can_manage(&guild, msg)?;
+
+                (CycleTarget::Role(role.id), format!("**{}**", role.name))
+            };
+
+            let stopped = actions::cycles::stop_cycle(&guild_record, target, &connection)
+                .map_err(|e| CommandError(format!("Couldn't stop the cycle due to {}", e)))?;
+
+            if stopped == 0 {
+                format!("{} isn't cycling.", target_name)
+            } else {
+                format!("{} stopped cycling.", target_name)
+            }
+        }
+        "status" => {
+            let cycles = actions::cycles::find_for_guild(&guild_record, &connection);
+
+            if cycles.is_empty() {
+                "Nothing is cycling colours on this server.".to_string()
+            } else {
+                let now = utils::unix_timestamp();
+                let guild = guild.read();
+
+                cycles
+                    .iter()
+                    .map(|cycle| describe_cycle(cycle, &guild, msg, now, &connection))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+        }
+        act => {
+            return Err(CommandError(format!(
+                "No cycle action exists for the input \"{}\". Please check the help.",
+                act
+            )))
+        }
+    };
+
+    let cleanup_delay = guild_record.settings().cleanup_delay;
+    let self_reply = msg.channel_id.send_message(|m| m.content(reply))?;
+
+    delay_delete!(self_reply; cleanup_delay);
+
+    Ok(())
+}
+
+/// Cycling a role changes it for everyone who has it, so that's limited to people who can manage roles.
+fn check_can_manage(guild: &Guild, msg: &Message) -> Result<(), CommandError> {
+    if guild.member_permissions(msg.author.id).manage_roles() {
+        Ok(())
+    } else {
+        Err(CommandError(
+            "You need to be able to manage roles to cycle a role's colour.".to_string(),
+        ))
+    }
+}
+
+fn describe_colours(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|name| format!("`{}`", name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A line of the status list, like "**Rainbow**: `Red`, `Blue` every 1h, next change in 20m".
+fn describe_cycle(
+    cycle: &Cycle,
+    guild: &Guild,
+    msg: &Message,
+    now: i64,
+    connection: &PgConnection,
+) -> String {
+    let target = match CycleTarget::of(cycle) {
+        Some(CycleTarget::User(user)) if user == msg.author.id => "You".to_string(),
+        Some(CycleTarget::User(user)) => guild
+            .members
+            .get(&user)
+            .map(|member| member.display_name().to_string())
+            .unwrap_or_else(|| "Someone who left".to_string()),
+        Some(CycleTarget::Role(role)) => guild
+            .roles
+            .get(&role)
+            .map(|role| role.name.clone())
+            .unwrap_or_else(|| "A deleted role".to_string()),
+        None => "Nothing".to_string(),
+    };
+
+    let names = actions::cycles::find_cycle_colours(cycle, connection)
+        .into_iter()
+        .map(|colour| colour.name)
+        .collect::<Vec<_>>();
+    let next_change = (cycle.next_run - now).max(0) as u64;
+
+    format!(
+        "**{}**: {} every {}, next change in {}",
+        target,
+        describe_colours(&names),
+        utils::format_interval(cycle.interval_seconds as u64),
+        utils::format_interval(next_change)
+    )
+}
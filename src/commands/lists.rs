@@ -1,13 +1,10 @@
 use actions;
 use utils;
 
-use std::fs;
-
 use serenity::framework::standard::Args;
 use serenity::framework::standard::{CommandError, CreateCommand};
 use serenity::model::prelude::Message;
 use serenity::prelude::Context;
-use serenity::Error as SerenityError;
 use serenity::CACHE;
 
 /// Sends the colour list into the user's DMs.
@@ -27,7 +24,6 @@ pub fn list_colours_exec(_: &mut Context, msg: &Message, _: Args) -> Result<(),
     let guild = utils::get_guild_result(msg)?;
     let guild = guild.read();
 
-    // TODO: Options and stuff, customization for the whole family!
     let guild_record =
         actions::guilds::convert_guild_to_record(guild.id, &connection).ok_or_else(|| {
             CommandError("No guild record found, you should create some colours first.".to_string())
@@ -35,20 +31,36 @@ pub fn list_colours_exec(_: &mut Context, msg: &Message, _: Args) -> Result<(),
     let colours = actions::colours::find_all(&guild_record, &connection)
         .ok_or_else(|| CommandError("Error getting the colours for the guild.".to_string()))?;
 
-    let colour_list_path = actions::colours::generate_colour_image(&colours, &guild)?;
-
-    msg.author
-        .create_dm_channel()?
-        .send_files(vec![colour_list_path.as_str()], |msg| {
-            msg.content(format!(
-                "Here are the colours for the guild \"{}\".",
-                guild.name
-            ))
-        }).and_then(|_| {
-            fs::remove_file(colour_list_path).map_err(|_| {
-                SerenityError::Other("Error trying to delete the leftover colour image")
-            })
+    let dm_channel = msg.author.create_dm_channel()?;
+
+    let locked = actions::requirements::find_locked_ids(&guild_record, &connection);
+
+    let colour_list_images = actions::colours::generate_colour_images(
+        &colours,
+        &locked,
+        &guild,
+        &guild_record.settings(),
+    )?;
+
+    let page_count = colour_list_images.len();
+
+    for (page, image) in colour_list_images.iter().enumerate() {
+        dm_channel.send_files(vec![image.as_attachment()], |msg| {
+            if page_count == 1 {
+                msg.content(format!(
+                    "Here are the colours for the guild \"{}\".",
+                    guild.name
+                ))
+            } else {
+                msg.content(format!(
+                    "Here are the colours for the guild \"{}\". (page {} of {})",
+                    guild.name,
+                    page + 1,
+                    page_count
+                ))
+            }
         })?;
+    }
 
     let reply = msg.channel_id.send_message(|msg| {
         msg.content("A copy of the colour list has been sent to your DMs. To keep one in the server, set up a colour channel.")
@@ -1,6 +1,11 @@
+pub mod aliases;
 pub mod channels;
+pub mod cycles;
 pub mod lists;
+pub mod palettes;
+pub mod requirements;
 pub mod roles;
+pub mod settings;
 /// Holds all end-point user commands the bot will use.
 /// The actual function that will be invoked on the command call should be seperate from
 /// the command configuration object.
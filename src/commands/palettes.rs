@@ -0,0 +1,216 @@
+use actions;
+use actions::palettes::{ConflictPolicy, ImportAction, PaletteFormat};
+use collector;
+use utils;
+
+use serenity::framework::standard::{Args, CommandError, CreateCommand};
+use serenity::model::permissions::Permissions;
+use serenity::model::prelude::Message;
+use serenity::prelude::Context;
+use serenity::CACHE;
+
+use bigdecimal::BigDecimal;
+use num_traits::cast::FromPrimitive;
+use prettytable::Table;
+
+/// The preview gets cut off after this many colours so it fits into a message.
+const PREVIEW_ROWS: usize = 10;
+
+/// Sends every colour in the guild as a file, so it can be backed up or moved to another server.
+pub fn export_colours(cmd: CreateCommand) -> CreateCommand {
+    cmd.batch_known_as(&["export_colours", "export_colors", "backup"])
+        .desc("Sends you a file with every colour on the server, along with its aliases and role name.")
+        .help_available(true)
+        .required_permissions(Permissions::MANAGE_ROLES)
+        .usage("[json | csv]")
+        .example("csv")
+        .max_args(1)
+        .exec(export_colours_exec)
+}
+
+pub fn export_colours_exec(_: &mut Context, msg: &Message, mut args: Args) -> Result<(), CommandError> {
+    let connection = utils::get_connection_or_panic();
+
+    let format = match args.single::<String>() {
+        Ok(format) => format
+            .parse::<PaletteFormat>()
+            .map_err(|e| CommandError(e.to_string()))?,
+        Err(_) => PaletteFormat::Json,
+    };
+
+    let guild = utils::get_guild_result(msg)?;
+    let guild = guild.read();
+
+    let guild_record =
+        actions::guilds::convert_guild_to_record(guild.id, &connection).ok_or_else(|| {
+            CommandError("No guild record found, you should create some colours first.".to_string())
+        })?;
+    let colours = actions::colours::find_all(&guild_record, &connection)
+        .ok_or_else(|| CommandError("Error getting the colours for the guild.".to_string()))?;
+
+    let palette = actions::palettes::build_palette(&colours, &guild, &connection);
+    let data = actions::palettes::export_palette(&palette, format)
+        .map_err(|e| CommandError(format!("Couldn't write the colours out due to {}", e)))?;
+    let filename = format!("{}-colours.{}", guild.id, format.extension());
+
+    let dm_channel = msg.author.create_dm_channel()?;
+
+    dm_channel.send_files(vec![(data.as_bytes(), filename.as_str())], |m| {
+        m.content(format!(
+            "Here are the {} colours for the guild \"{}\". Use `import` with the file attached to bring them into a server.",
+            palette.len(),
+            guild.name
+        ))
+    })?;
+
+    Ok(())
+}
+
+/// Creates the colours from an exported file.
+pub fn import_colours(cmd: CreateCommand) -> CreateCommand {
+    cmd.batch_known_as(&["import_colours", "import_colors"])
+        .desc("Creates the colours from an attached JSON or CSV file. Colours whose name is already taken are skipped, renamed or overwritten. Add `dry` to only see what would happen.")
+        .help_available(true)
+        .required_permissions(Permissions::MANAGE_ROLES)
+        .usage("[skip | rename | overwrite] [dry]")
+        .example("rename dry")
+        .max_args(3)
+        .exec(import_colours_exec)
+}
+
+pub fn import_colours_exec(_: &mut Context, msg: &Message, args: Args) -> Result<(), CommandError> {
+    let connection = utils::get_connection_or_panic();
+
+    let mut policy = ConflictPolicy::default();
+    let mut format = None;
+    let mut dry_run = false;
+
+    for arg in args.full().split_whitespace() {
+        match arg.to_lowercase().as_str() {
+            "dry" | "dry-run" | "preview" => dry_run = true,
+            other => match (other.parse::<ConflictPolicy>(), other.parse::<PaletteFormat>()) {
+                (Ok(parsed), _) => policy = parsed,
+                (_, Ok(parsed)) => format = Some(parsed),
+                (Err(e), _) => return Err(CommandError(e.to_string())),
+            },
+        }
+    }
+
+    let attachment = msg.attachments.first().ok_or_else(|| {
+        CommandError("Attach the JSON or CSV file with the colours to import.".to_string())
+    })?;
+
+    if attachment.size > actions::palettes::MAX_IMPORT_SIZE {
+        return Err(CommandError(
+            "The file is too big, it should only hold a list of colours.".to_string(),
+        ));
+    }
+
+    let format = format
+        .or_else(|| PaletteFormat::from_filename(&attachment.filename))
+        .ok_or_else(|| {
+            CommandError(
+                "Couldn't tell what kind of file that is, add `json` or `csv` to the command."
+                    .to_string(),
+            )
+        })?;
+    let data = String::from_utf8(attachment.download()?)
+        .map_err(|_| CommandError("The file isn't readable as text.".to_string()))?;
+
+    let entries = actions::palettes::parse_palette(&data, format)
+        .map_err(|e| CommandError(e.to_string()))?;
+
+    // importing is a way of setting up a new server, so the guild might not have a record yet.
+    let guild_id = utils::get_guild_result(msg)?.read().id;
+    let guild_record = BigDecimal::from_u64(guild_id.0)
+        .ok_or_else(|| CommandError("Couldn't read the guild's id.".to_string()))
+        .and_then(|id| {
+            actions::guilds::check_or_create_guild(&id, &connection)
+                .into_result()
+                .map_err(|e| CommandError(format!("Couldn't find the guild due to {}", e)))
+        })?;
+    let existing = actions::colours::find_all(&guild_record, &connection).unwrap_or_default();
+
+    let plan = actions::palettes::plan_import(entries, &existing, policy);
+    let preview = describe_plan(&plan);
+    let cleanup_delay = guild_record.settings().cleanup_delay;
+
+    if dry_run {
+        let self_reply = msg
+            .channel_id
+            .send_message(|m| m.content(format!("{}\nNothing was changed.", preview)))?;
+
+        delay_delete!(self_reply; cleanup_delay);
+
+        return Ok(());
+    }
+
+    if !plan.iter().any(|action| match *action {
+        ImportAction::Skip { .. } => false,
+        _ => true,
+    }) {
+        return Err(CommandError(
+            "Every colour in the file would be skipped, nothing to import.".to_string(),
+        ));
+    }
+
+    // no guild lock is held while waiting for the answer.
+    if !collector::ask_confirmation(msg, &format!("{}\nImport these colours?", preview))? {
+        let self_reply = msg
+            .channel_id
+            .send_message(|m| m.content("Import cancelled, nothing was changed."))?;
+
+        delay_delete!(self_reply; cleanup_delay);
+
+        return Ok(());
+    }
+
+    let imported = {
+        let guild = utils::get_guild_result(msg)?;
+        let guild = guild.read();
+
+        actions::palettes::apply_import(&plan, &guild, &guild_record, &connection)
+    };
+
+    // create_role doesn't update the cached guild we had, so get it again for the list.
+    // a failed import can still have created some colours, so the list is refreshed either way.
+    let guild = utils::get_guild_result(msg)?;
+    let self_id = CACHE.read().user.id.0;
+    let _ = actions::guilds::update_channel_message(&guild.read(), self_id, &connection, false);
+
+    let (created, overwritten) = imported.map_err(|e| CommandError(e.to_string()))?;
+
+    let self_reply = msg.channel_id.send_message(|m| {
+        m.content(format!(
+            "Imported the colours, {} created and {} overwritten.",
+            created, overwritten
+        ))
+    })?;
+
+    delay_delete!(self_reply; cleanup_delay);
+
+    Ok(())
+}
+
+/// A table of what the import does with each colour, cut short for big files.
+fn describe_plan(plan: &[ImportAction]) -> String {
+    let mut rows = vec![row!["Name", "Colour", "Action"]];
+
+    rows.extend(plan.iter().take(PREVIEW_ROWS).map(|action| {
+        let entry = action.entry();
+
+        row![entry.name, entry.hex, action.describe()]
+    }));
+
+    let table = Table::init(rows);
+
+    if plan.len() > PREVIEW_ROWS {
+        format!(
+            "```{}```...and {} more colours.",
+            table,
+            plan.len() - PREVIEW_ROWS
+        )
+    } else {
+        format!("```{}```", table)
+    }
+}
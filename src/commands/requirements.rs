@@ -0,0 +1,134 @@
+use actions;
+use db::models::ColourRequirement;
+use utils;
+
+use serenity::framework::standard::{Args, CommandError, CreateCommand};
+use serenity::model::permissions::Permissions;
+use serenity::model::prelude::Message;
+use serenity::prelude::Context;
+use serenity::CACHE;
+
+use bigdecimal::BigDecimal;
+use num_traits::cast::FromPrimitive;
+
+/// Shows or changes what members need before they can pick a colour.
+pub fn require(cmd: CreateCommand) -> CreateCommand {
+    cmd.batch_known_as(&["requirements", "lock"])
+        .desc("Limits a colour to members with certain roles, boosters, or members who've been in the server for a while.")
+        .help_available(true)
+        .required_permissions(Permissions::MANAGE_ROLES)
+        .usage("<colour name> [roles <role>, <role>... | boosters <on | off> | days <amount> | none]")
+        .example("\"Gold\" roles Supporter, Patron")
+        .min_args(1)
+        .exec(require_exec)
+}
+
+pub fn require_exec(_: &mut Context, msg: &Message, mut args: Args) -> Result<(), CommandError> {
+    let connection = utils::get_connection_or_panic();
+
+    let guild = utils::get_guild_result(msg)?;
+    let guild_id = guild.read().id;
+    let guild_record = actions::guilds::convert_guild_to_record(guild_id, &connection)
+        .ok_or_else(|| CommandError("No guild was found in the database. This means you have not created a colour on this server yet.".to_string()))?;
+
+    let colour_name = args.single_quoted::<String>()?;
+    let action = args.single::<String>().unwrap_or_default().to_lowercase();
+    let value = args.rest().trim().to_string();
+
+    let colour =
+        utils::pick_colour_from_name(msg, &colour_name, &guild_record, &connection, true)?
+            .ok_or_else(|| {
+                CommandError(format!(
+                    "The colour {} could not be found. Check your spelling!",
+                    colour_name
+                ))
+            })?;
+
+    let mut requirement = actions::requirements::find_for_colour(&colour, &connection)
+        .unwrap_or_else(|| ColourRequirement::none_for(colour.id.clone()));
+
+    match action.as_str() {
+        "" => (),
+        "roles" | "role" => {
+            requirement.role_ids = if value.is_empty() || value.to_lowercase() == "none" {
+                vec![]
+            } else {
+                let guild = guild.read();
+
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(|name| {
+                        let role = utils::search_role_from_name(&guild, name)?;
+
+                        BigDecimal::from_u64(role.id.0)
+                            .ok_or_else(|| CommandError("Couldn't read the role's id.".to_string()))
+                    }).collect::<Result<Vec<_>, _>>()?
+            };
+        }
+        "boosters" | "booster" => {
+            requirement.boosters_only = match value.to_lowercase().as_str() {
+                "" | "on" | "yes" | "true" => true,
+                "off" | "no" | "false" => false,
+                other => {
+                    return Err(CommandError(format!(
+                        "Couldn't read \"{}\", use `on` or `off`.",
+                        other
+                    )))
+                }
+            };
+        }
+        "days" | "day" => {
+            requirement.min_days = value.parse::<u16>().map(i32::from).map_err(|_| {
+                CommandError(format!("Couldn't read \"{}\" as an amount of days.", value))
+            })?;
+        }
+        "none" | "clear" => {
+            requirement = ColourRequirement::none_for(colour.id.clone());
+        }
+        act => {
+            return Err(CommandError(format!(
+                "No requirement exists for the input \"{}\". Please check the help.",
+                act
+            )))
+        }
+    }
+
+    if !action.is_empty() {
+        requirement = actions::requirements::save_requirement(&requirement, &connection)
+            .map_err(|e| CommandError(format!("Couldn't save the requirements due to {}", e)))?;
+
+        let self_id = CACHE.read().user.id.0;
+        let _ = actions::guilds::update_channel_message(&guild.read(), self_id, &connection, false);
+    }
+
+    let settings = guild_record.settings();
+
+    let mut reply = if requirement.is_empty() {
+        format!("Anyone can pick **{}**.", colour.name)
+    } else {
+        let needs = actions::requirements::list_requirements(&requirement);
+
+        format!(
+            "**{}** needs {}.",
+            colour.name,
+            actions::requirements::describe_requirements(&needs, &guild.read())
+        )
+    };
+
+    if requirement.boosters_only
+        && actions::requirements::find_booster_role(&guild.read(), &settings).is_none()
+    {
+        reply.push_str(
+            "\nNo booster role was found, so nobody can pick it yet. Use `settings booster_role = <role>` to pick the role boosters get.",
+        );
+    }
+
+    let cleanup_delay = settings.cleanup_delay;
+    let self_reply = msg.channel_id.send_message(|m| m.content(reply))?;
+
+    delay_delete!(self_reply; cleanup_delay);
+
+    Ok(())
+}
@@ -1,9 +1,11 @@
 use actions::{self, colours::UpdateActionParams};
-// use collector::{CollectorItem, CollectorValue, CustomCollector};
+use collector;
+use colours::distance::describe_distance;
+use colours::parsing;
 use colours::ParsedColour;
 use constants::commands::roles_edit;
+use db::settings::AmbiguousMatch;
 use utils;
-// use COLLECTOR;
 
 use std::str::FromStr;
 
@@ -18,23 +20,29 @@ use prettytable::Table;
 /// Most basic but most important of commands, gives the user the colour they requested, or not if it doesn't exist.
 pub fn get_colour(cmd: CreateCommand) -> CreateCommand {
     cmd.batch_known_as(&["getc", "getcolour", "getcolor", "colour", "color"])
-        .desc("Finds a colour role from a name and assigns it to you.")
+        .desc("Finds a colour role from a name and assigns it to you. Add `for` and a duration to only have it for a while.")
         .help_available(true)
-        .usage("colour")
-        .example("red")
+        .usage("colour [for <duration>]")
+        .example("gold for 24h")
         .min_args(1)
         .exec(get_colour_exec)
 }
 
 pub fn get_colour_exec(_: &mut Context, msg: &Message, args: Args) -> Result<(), CommandError> {
     let conn = utils::get_connection_or_panic();
-    let colour_name = args.multiple::<String>()?.join(" ");
+    let (colour_name, duration) =
+        actions::temporary::split_duration(&args.multiple::<String>()?.join(" "));
+    let duration = match duration {
+        Some(duration) => Some(
+            actions::temporary::check_duration(duration).map_err(|e| CommandError(e.to_string()))?,
+        ),
+        None => None,
+    };
 
     let guild = msg.guild()
         .ok_or_else(|| CommandError("Could not find guild. This command only works in a guild, if you are a in a PM / Group, please only use commands that do not require any roles".to_string()))?;
     let discord_guild = guild.clone();
-    let discord_guild = discord_guild.write();
-    let discord_guild_id = discord_guild.id;
+    let discord_guild_id = discord_guild.read().id;
 
     let guild = actions::guilds::convert_guild_to_record(discord_guild_id, &conn)
         .or_else(|| {
@@ -43,27 +51,103 @@ pub fn get_colour_exec(_: &mut Context, msg: &Message, args: Args) -> Result<(),
                 .ok()
         }).ok_or_else(|| CommandError("Could not find/create a guild.".to_string()))?;
 
-    let colour = actions::colours::find_from_name(&colour_name, &guild, &conn)
-        .ok_or_else(|| CommandError(format!("Could not find a name that matches {}. Make sure you've used the correct spelling, and that you are typing a valid colour name like (red), and not a hex code like (#fff)", colour_name)))?;
+    let settings = guild.settings();
+    let in_colour_channel = guild
+        .channel_id
+        .as_ref()
+        .and_then(|id| id.to_u64())
+        .map(|id| id == msg.channel_id.0)
+        .unwrap_or(false);
+    let ask = !in_colour_channel || settings.ambiguous_match == AmbiguousMatch::Ask;
+
+    let colour_code = if in_colour_channel && settings.colour_codes {
+        parsing::parse_colour_code(&colour_name)
+    } else {
+        None
+    };
+
+    let (colour, message_contents) = match colour_code {
+        Some(code) => {
+            let code = code.map_err(|e| CommandError(e.to_string()))?;
+            let colours = actions::colours::find_all(&guild, &conn).unwrap_or_default();
+
+            let (colour, distance) = {
+                let discord_guild = discord_guild.read();
+                actions::colours::find_nearest_to_colour(&code, &colours, &discord_guild)
+            }.ok_or_else(|| CommandError("There are no colours to pick from yet.".to_string()))?;
+
+            if let Some(max_distance) = settings.max_colour_distance {
+                if distance > max_distance {
+                    return Err(CommandError(format!(
+                        "No colour is close enough to {}, the closest is `{}` and that's {} ({:.1} away).",
+                        code,
+                        colour.name,
+                        describe_distance(distance),
+                        distance
+                    )));
+                }
+            }
+
+            let message_contents = format!(
+                "Closest colour to {}: `{}`, {} ({:.1} away), applying role now...",
+                code,
+                colour.name,
+                describe_distance(distance),
+                distance
+            );
+
+            (colour, message_contents)
+        }
+        None => {
+            let hint = if settings.colour_codes {
+                "a colour code like (#fff) only works in the colour channel"
+            } else {
+                "not a hex code like (#fff)"
+            };
+
+            let colour = utils::pick_colour_from_name(msg, &colour_name, &guild, &conn, ask)?
+                .ok_or_else(|| CommandError(format!("Could not find a name that matches {}. Make sure you've used the correct spelling, and that you are typing a valid colour name like (red), {}", colour_name, hint)))?;
+
+            let names_differ = colour.name.to_lowercase() != colour_name.to_lowercase();
+            let message_contents = if names_differ {
+                format!(
+                    "Using nearest match for {}: `{}`, applying role now...",
+                    colour_name, colour.name
+                )
+            } else {
+                "Colour found, applying role now...".to_string()
+            };
+
+            (colour, message_contents)
+        }
+    };
+
+    let message_contents = match duration {
+        Some(duration) => format!(
+            "{} It comes off again in {}.",
+            message_contents,
+            utils::format_interval(duration)
+        ),
+        None => message_contents,
+    };
+
+    // only lock the guild once the user is done picking, the lock would hold up other events while waiting.
+    let discord_guild = discord_guild.write();
+
+    actions::requirements::check_member(
+        &colour,
+        msg.author.id,
+        &discord_guild,
+        utils::unix_timestamp(),
+        &conn,
+    ).map_err(|e| CommandError(e.to_string()))?;
 
     let channel = msg
         .channel()
         .ok_or_else(|| CommandError("Channel is null".to_string()))?;
     let channel_id = channel.id();
 
-    let colour_init_msg = channel_id.send_message(|msg| {
-        let names_differ = colour.name.to_lowercase() != colour_name.to_lowercase();
-        let message_contents = if names_differ {
-            format!(
-                "Using nearest match for {}: `{}`, applying role now...",
-                colour_name, colour.name
-            )
-        } else {
-            "Colour found, applying role now...".to_string()
-        };
-
-        msg.content(message_contents)
-    })?;
+    let colour_init_msg = channel_id.send_message(|msg| msg.content(message_contents))?;
 
     delay_delete!(colour_init_msg; 4);
 
@@ -72,7 +156,119 @@ pub fn get_colour_exec(_: &mut Context, msg: &Message, args: Args) -> Result<(),
         CommandError("Role is missing from the guild. Removing role from the list so that this doesn't occur again.".to_string())
     })?;
 
-    actions::colours::assign_colour_to_user(&msg.author, discord_guild, &colour_role, &conn)?;
+    match duration {
+        Some(duration) => actions::temporary::give_temporary_colour(
+            &msg.author,
+            discord_guild,
+            &colour,
+            &colour_role,
+            utils::unix_timestamp() + duration as i64,
+            &conn,
+        )?,
+        None => {
+            // picking a colour for good replaces a temporary one.
+            let _ = actions::temporary::cancel_for_user(&guild, msg.author.id, &conn);
+            actions::colours::assign_colour_to_user(&msg.author, discord_guild, &colour_role, &conn)?
+        }
+    }
+
+    Ok(())
+}
+
+/// Hands a colour to everyone with a role for a while, like an event colour.
+pub fn grant_colour(cmd: CreateCommand) -> CreateCommand {
+    cmd.batch_known_as(&["grantc", "grantcolour", "grantcolor"])
+        .desc("Gives everyone with a role a colour for a limited time, their old colours come back once it runs out.")
+        .required_permissions(Permissions::MANAGE_ROLES)
+        .help_available(true)
+        .usage("<@role/role name> <colour name> for <duration>")
+        .example("@Attendees gold for 2d")
+        .min_args(4)
+        .exec(grant_colour_exec)
+}
+
+pub fn grant_colour_exec(_: &mut Context, msg: &Message, mut args: Args) -> Result<(), CommandError> {
+    let connection = utils::get_connection_or_panic();
+
+    let guild = utils::get_guild_result(msg)?;
+    let guild_id = guild.read().id;
+    let guild_record = actions::guilds::convert_guild_to_record(guild_id, &connection)
+        .ok_or_else(|| CommandError("No guild was found in the database. This means you have not created a colour on this server yet.".to_string()))?;
+
+    let role = utils::get_or_search_role_from_arg(&guild.read(), &mut args)?;
+
+    let (colour_name, duration) = actions::temporary::split_duration(args.rest());
+    let duration = duration
+        .ok_or_else(|| {
+            CommandError("No duration was given, end the command with something like `for 24h`.".to_string())
+        }).and_then(|duration| {
+            actions::temporary::check_duration(duration).map_err(|e| CommandError(e.to_string()))
+        })?;
+
+    let colour = utils::pick_colour_from_name(msg, &colour_name, &guild_record, &connection, true)?
+        .ok_or_else(|| {
+            CommandError(format!(
+                "The colour {} could not be found. Check your spelling!",
+                colour_name
+            ))
+        })?;
+
+    // only cached members can be checked for the role, big guilds don't send all of them.
+    let (colour_role, users, unchecked) = {
+        let guild = guild.read();
+        let colour_role = actions::colours::search_role(&colour, &guild)
+            .ok_or_else(|| CommandError("Couldn't find the colour in the guild!".to_string()))?;
+        let users = guild
+            .members
+            .values()
+            .filter(|member| member.roles.contains(&role.id))
+            .map(|member| member.user.read().clone())
+            .collect::<Vec<_>>();
+        let unchecked = guild.member_count.saturating_sub(guild.members.len() as u64);
+
+        (colour_role, users, unchecked)
+    };
+
+    let expires_at = utils::unix_timestamp() + duration as i64;
+
+    // one member failing, say from leaving halfway through, shouldn't stop the rest from getting it.
+    let given = users
+        .iter()
+        .filter(|user| {
+            actions::temporary::give_temporary_colour(
+                user,
+                guild.write(),
+                &colour,
+                &colour_role,
+                expires_at,
+                &connection,
+            ).is_ok()
+        }).count();
+
+    let failed = users.len() - given;
+    let mut content = format!(
+        "Gave `{}` to {} members with **{}** for {}.",
+        colour.name,
+        given,
+        role.name,
+        utils::format_interval(duration)
+    );
+
+    if failed > 0 {
+        content.push_str(&format!("\n{} members couldn't be given the colour.", failed));
+    }
+
+    if unchecked > 0 {
+        content.push_str(&format!(
+            "\n{} members aren't loaded yet, so they couldn't be checked for the role.",
+            unchecked
+        ));
+    }
+
+    let cleanup_delay = guild_record.settings().cleanup_delay;
+    let reply = msg.channel_id.send_message(|m| m.content(content))?;
+
+    delay_delete!(reply; cleanup_delay);
 
     Ok(())
 }
@@ -130,6 +326,99 @@ pub fn add_colour_exec(_: &mut Context, msg: &Message, mut args: Args) -> Result
     Ok(())
 }
 
+/// Adds every role that looks like a colour role at once, for servers coming from another colour bot.
+pub fn adopt_colours(cmd: CreateCommand) -> CreateCommand {
+    cmd.batch_known_as(&["adoptc", "adoptall"])
+        .desc("Finds the roles that only give a colour and adds them to the colour list after you confirm.")
+        .required_permissions(Permissions::MANAGE_ROLES)
+        .help_available(true)
+        .usage("")
+        .example("")
+        .max_args(0)
+        .exec(adopt_colours_exec)
+}
+
+pub fn adopt_colours_exec(_: &mut Context, msg: &Message, _: Args) -> Result<(), CommandError> {
+    // the preview gets cut off after this many roles so it fits into the question.
+    const PREVIEW_ROWS: usize = 10;
+
+    let connection = utils::get_connection_or_panic();
+
+    let guild = utils::get_guild_result(msg)?;
+    let guild_id = guild.read().id;
+
+    let guild_record = actions::guilds::convert_guild_to_record(guild_id, &connection);
+    let existing = guild_record
+        .as_ref()
+        .and_then(|record| actions::colours::find_all(record, &connection))
+        .unwrap_or_default();
+    let cleanup_delay = guild_record
+        .map(|record| record.settings())
+        .unwrap_or_default()
+        .cleanup_delay;
+
+    let self_id = CACHE.read().user.id;
+    let adoptable = actions::colours::find_adoptable_roles(&guild.read(), self_id, &existing);
+
+    if adoptable.is_empty() {
+        return Err(CommandError(
+            "No roles that only give a colour were found below the bot's highest role, add them one at a time with `add` instead."
+                .to_string(),
+        ));
+    }
+
+    let mut rows = vec![row!["Role", "Colour", "Name"]];
+
+    rows.extend(adoptable.iter().take(PREVIEW_ROWS).map(|adopted| {
+        row![
+            adopted.role.name,
+            ParsedColour::from(adopted.role.colour),
+            adopted.name
+        ]
+    }));
+
+    let preview = if adoptable.len() > PREVIEW_ROWS {
+        format!(
+            "```{}```...and {} more roles.",
+            Table::init(rows),
+            adoptable.len() - PREVIEW_ROWS
+        )
+    } else {
+        format!("```{}```", Table::init(rows))
+    };
+
+    // no guild lock is held while waiting for the answer.
+    let question = format!(
+        "{}\nAdd these {} roles to the colour list?",
+        preview,
+        adoptable.len()
+    );
+
+    if !collector::ask_confirmation(msg, &question)? {
+        let reply = msg
+            .channel_id
+            .send_message(|m| m.content("Nothing was added."))?;
+
+        delay_delete!(reply; cleanup_delay);
+
+        return Ok(());
+    }
+
+    let adopted = actions::colours::adopt_roles(&adoptable, guild_id, &connection)
+        .map_err(|e| CommandError(format!("Couldn't save the colours due to {}", e)))?;
+
+    let self_id = CACHE.read().user.id.0;
+    actions::guilds::update_channel_message(&guild.read(), self_id, &connection, false)?;
+
+    let reply = msg.channel_id.send_message(|m| {
+        m.content(format!("Added {} colours to the list.", adopted.len()))
+    })?;
+
+    delay_delete!(reply; cleanup_delay);
+
+    Ok(())
+}
+
 pub fn remove_colour(cmd: CreateCommand) -> CreateCommand {
     cmd.batch_known_as(&["removec", "rm", "rmcolour", "rmcolor"])
         .desc("Removes a colour and its role from the list. Optionally the discord role can be preserved")
@@ -149,13 +438,13 @@ pub fn remove_colour_exec(
     let connection = utils::get_connection_or_panic();
 
     let guild_res = utils::get_guild_result(&msg)?;
-    let guild = guild_res.read();
+    let guild_id = guild_res.read().id;
 
-    let guild_record = actions::guilds::convert_guild_to_record(guild.id, &connection)
+    let guild_record = actions::guilds::convert_guild_to_record(guild_id, &connection)
         .ok_or_else(|| CommandError("Guild does not exist. This means that you've never created a colour or used any colour related commands before.".to_string()))?;
 
     let colour_name = args.single_quoted::<String>()?;
-    let colour = actions::colours::find_from_name(&colour_name, &guild_record, &connection)
+    let colour = utils::pick_colour_from_name(msg, &colour_name, &guild_record, &connection, true)?
         .ok_or_else(|| {
             CommandError(format!(
                 "The colour {} could not be found. Check your spelling!",
@@ -165,12 +454,23 @@ pub fn remove_colour_exec(
 
     let keep_discord_role = args.single::<bool>().unwrap_or(false);
 
+    let question = if keep_discord_role {
+        format!("Remove **{}** from the colour list?", colour.name)
+    } else {
+        format!("Remove **{}** and delete its role?", colour.name)
+    };
+
+    if !collector::ask_confirmation(msg, &question)? {
+        return Err(CommandError("Cancelled, the colour was kept.".to_string()));
+    }
+
     actions::colours::remove_record(&colour, &connection).map_err(|_| {
         CommandError("Error while trying to delete the record. Aborting!".to_string())
     })?;
 
-    let cache = CACHE.read();
-    let self_id = cache.user.id.0;
+    // the guild and cache aren't locked until the user has answered, or their reply couldn't come through.
+    let self_id = CACHE.read().user.id.0;
+    let guild = guild_res.read();
 
     actions::guilds::update_channel_message(&guild, self_id, &connection, false)?;
 
@@ -198,7 +498,7 @@ pub fn generate_colour(cmd: CreateCommand) -> CreateCommand {
         .help_available(true)
         .required_permissions(Permissions::MANAGE_ROLES)
         .example("#ff0000 green")
-        .usage("<colour code> [colour name (will be generated if you dont provide one)]")
+        .usage("<colour code, rgb(), hsl(), hsv() or css name> [colour name (will be generated if you dont provide one)]")
         .min_args(1)
         .exec(generate_colour_exec)
 }
@@ -210,7 +510,7 @@ pub fn generate_colour_exec(
 ) -> Result<(), CommandError> {
     let connection = utils::get_connection_or_panic();
 
-    let colour = args.single::<ParsedColour>()?;
+    let colour = utils::get_colour_from_args(&mut args)?;
     let name = args
         .iter::<String>()
         .collect::<Result<Vec<String>, _>>()
@@ -227,18 +527,12 @@ pub fn generate_colour_exec(
             }
         })?;
 
-    let role_colour = colour.as_role_colour();
-
     let guild = utils::get_guild_result(msg)?;
     let guild = guild.write();
 
     let guild_id = guild.id;
 
-    let new_role = guild.create_role(|role| {
-        role.name(&name)
-            .colour(u64::from(role_colour.0))
-            .mentionable(false)
-    })?;
+    let new_role = actions::colours::create_colour_role(&guild, &name, &colour)?;
 
     let colour_struct = actions::colours::convert_role_to_record_struct(name, &new_role, guild_id)
         .ok_or_else(|| {
@@ -291,8 +585,7 @@ pub fn edit_colour_exec(
     let colour_name = args.single_quoted::<String>()?;
     let action = args.multiple::<String>()?.join(" ");
 
-    let cache = CACHE.read();
-    let self_id = cache.user.id.0;
+    let self_id = CACHE.read().user.id.0;
 
     if action.is_empty() {
         return Err(CommandError(
@@ -307,7 +600,7 @@ pub fn edit_colour_exec(
         .ok_or_else(|| CommandError("No guild was found in the database. This means you have not created a colour on this server yet.".to_string()))?;
 
     // no currying ;(
-    let closest_colour = actions::colours::find_from_name(&colour_name, &guild_record, &connection)
+    let closest_colour = utils::pick_colour_from_name(msg, &colour_name, &guild_record, &connection, true)?
         .ok_or_else(|| {
             CommandError(format!("No colour name close enough to {} could be found. Check if the colour exists and that you've spelt it correctly.", &colour_name))
         })?;
@@ -378,8 +671,8 @@ pub fn edit_colour_exec(
         }
 
         ("colour", Some(unparsed_colour)) => {
-            let parsed_colour = ParsedColour::from_str(unparsed_colour).map_err(|_| {
-                CommandError("The colour given was not a valid hex code.".to_string())
+            let parsed_colour = ParsedColour::from_str(unparsed_colour).map_err(|e| {
+                CommandError(format!("The colour given could not be read. {}", e))
             })?;
 
             actions::colours::update_colour_and_role(
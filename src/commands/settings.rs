@@ -0,0 +1,217 @@
+use actions;
+use utils;
+
+use serenity::framework::standard::Args;
+use serenity::framework::standard::{CommandError, CreateCommand};
+use serenity::model::permissions::Permissions;
+use serenity::model::prelude::{Guild, Message};
+use serenity::prelude::Context;
+use serenity::CACHE;
+
+use prettytable::Table;
+
+/// Shows or changes the settings stored for the guild.
+pub fn settings(cmd: CreateCommand) -> CreateCommand {
+    cmd.batch_known_as(&["setting", "config", "configure"])
+        .desc("Shows the settings for this server, or changes one of them.")
+        .help_available(true)
+        .required_permissions(Permissions::MANAGE_GUILD)
+        .usage("[<setting> = <value> | reset <setting>]")
+        .example("fuzzy_tolerance = 3")
+        .exec(settings_exec)
+}
+
+pub fn settings_exec(_: &mut Context, msg: &Message, args: Args) -> Result<(), CommandError> {
+    let connection = utils::get_connection_or_panic();
+
+    let guild = utils::get_guild_result(msg)?;
+    let guild = guild.read();
+
+    let guild_record = actions::guilds::convert_guild_to_record(guild.id, &connection)
+        .or_else(|| {
+            actions::guilds::create_new_record_from_guild(guild.id)
+                .and_then(|record| actions::guilds::save_record_into_db(&record, &connection))
+                .ok()
+        }).ok_or_else(|| {
+            CommandError(
+                "Couldn't convert this guild into its database representation!".to_string(),
+            )
+        })?;
+
+    let mut settings = guild_record.settings();
+    let action = args.full().trim().to_string();
+
+    if action.is_empty() {
+        let mut info_table = Table::init(vec![row!["Setting", "Value"]]);
+
+        for (key, value) in settings.entries() {
+            info_table.add_row(row![key, value]);
+        }
+
+        let self_reply = msg
+            .channel_id
+            .send_message(|m| m.content(format!("```{}```", info_table)))?;
+        let cleanup_delay = settings.cleanup_delay;
+
+        delay_delete!(self_reply; cleanup_delay);
+
+        return Ok(());
+    }
+
+    let split_action = action.splitn(2, '=').map(str::trim).collect::<Vec<_>>();
+
+    let key = match (split_action.first(), split_action.get(1)) {
+        (Some(key), Some(value)) => {
+            let value = find_role_value(&guild, key, value);
+            settings.set(key, &value)?;
+            key.to_string()
+        }
+        (Some(reset), None) if reset.to_lowercase().starts_with("reset ") => {
+            let key = reset["reset ".len()..].trim();
+            settings.reset(key)?;
+            key.to_string()
+        }
+        _ => {
+            return Err(CommandError(format!(
+                "Couldn't understand \"{}\". Use `<setting> = <value>` or `reset <setting>`.",
+                action
+            )))
+        }
+    };
+
+    actions::guilds::update_settings(&guild_record, &settings, &connection)
+        .map_err(|e| CommandError(format!("Could not save the settings due to {}", e)))?;
+
+    let value = settings.get(&key).unwrap_or_default();
+    let cleanup_delay = settings.cleanup_delay;
+
+    let self_reply = msg
+        .channel_id
+        .send_message(|m| m.content(format!("`{}` is now set to `{}`.", key, value)))?;
+
+    delay_delete!(self_reply; cleanup_delay);
+
+    let cache = CACHE.read();
+    let self_id = cache.user.id.0;
+
+    // the settings are already saved, a guild without colours just has no list to refresh.
+    let _ = actions::guilds::update_channel_message(&guild, self_id, &connection, false);
+
+    Ok(())
+}
+
+/// Role settings are stored by id, so a role name given for one is swapped for its id.
+/// Anything that isn't a role is passed on as is, for the setting to accept or refuse.
+fn find_role_value(guild: &Guild, key: &str, value: &str) -> String {
+    if !key.trim().eq_ignore_ascii_case("booster_role") {
+        return value.to_string();
+    }
+
+    utils::search_role_from_name(guild, value)
+        .map(|role| role.id.0.to_string())
+        .unwrap_or_else(|_| value.to_string())
+}
+
+/// Shows or changes the command prefixes the bot answers to in the guild.
+pub fn prefix(cmd: CreateCommand) -> CreateCommand {
+    cmd.batch_known_as(&["prefixes"])
+        .desc("Shows the command prefixes for this server, or changes them.")
+        .help_available(true)
+        .required_permissions(Permissions::MANAGE_GUILD)
+        .usage("[add <prefix> | remove <prefix> | set <prefixes...> | reset]")
+        .example("add ?c")
+        .exec(prefix_exec)
+}
+
+pub fn prefix_exec(_: &mut Context, msg: &Message, mut args: Args) -> Result<(), CommandError> {
+    let connection = utils::get_connection_or_panic();
+
+    let guild = utils::get_guild_result(msg)?;
+    let guild = guild.read();
+
+    let guild_record = actions::guilds::convert_guild_to_record(guild.id, &connection)
+        .or_else(|| {
+            actions::guilds::create_new_record_from_guild(guild.id)
+                .and_then(|record| actions::guilds::save_record_into_db(&record, &connection))
+                .ok()
+        }).ok_or_else(|| {
+            CommandError(
+                "Couldn't convert this guild into its database representation!".to_string(),
+            )
+        })?;
+
+    let mut settings = guild_record.settings();
+    let mut prefixes = settings.active_prefixes();
+
+    let action = args.single::<String>().unwrap_or_default().to_lowercase();
+    let value = args.rest().trim().to_lowercase();
+
+    match (action.as_str(), value.as_str()) {
+        ("", _) => {
+            let self_reply = msg.channel_id.send_message(|m| {
+                m.content(format!(
+                    "The prefixes for this server are: {}",
+                    prefixes
+                        .iter()
+                        .map(|p| format!("`{}`", p))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+            })?;
+            let cleanup_delay = settings.cleanup_delay;
+
+            delay_delete!(self_reply; cleanup_delay);
+
+            return Ok(());
+        }
+        ("add", prefix) if !prefix.is_empty() => {
+            prefixes.push(prefix.to_string());
+            settings.set("prefixes", &prefixes.join(" "))?;
+        }
+        ("remove", prefix) if !prefix.is_empty() => {
+            prefixes.retain(|p| p != prefix);
+
+            if prefixes.is_empty() {
+                return Err(CommandError(
+                    "A server needs at least one prefix. Use `reset` to go back to the defaults."
+                        .to_string(),
+                ));
+            }
+
+            settings.set("prefixes", &prefixes.join(" "))?;
+        }
+        ("set", list) if !list.is_empty() => {
+            settings.set("prefixes", list)?;
+        }
+        ("reset", _) => {
+            settings.reset("prefixes")?;
+        }
+        (act, _) => {
+            return Err(CommandError(format!(
+                "No prefix action exists for the input \"{}\". Please check the help.",
+                act
+            )));
+        }
+    }
+
+    actions::guilds::update_settings(&guild_record, &settings, &connection)
+        .map_err(|e| CommandError(format!("Could not save the prefixes due to {}", e)))?;
+
+    let cleanup_delay = settings.cleanup_delay;
+
+    let self_reply = msg.channel_id.send_message(|m| {
+        m.content(format!(
+            "The prefixes for this server are now: {}",
+            settings
+                .active_prefixes()
+                .iter()
+                .map(|p| format!("`{}`", p))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+    })?;
+
+    delay_delete!(self_reply; cleanup_delay);
+
+    Ok(())
+}
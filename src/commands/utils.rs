@@ -1,6 +1,10 @@
+use actions;
+use utils;
+
 use serenity::client::Context;
 use serenity::framework::standard::CreateCommand;
 use serenity::framework::standard::{Args, CommandError};
+use serenity::model::permissions::Permissions;
 use serenity::model::prelude::{Message, User};
 use serenity::utils::Colour;
 use serenity::CACHE;
@@ -45,3 +49,39 @@ pub fn info_exec(_ctx: &mut Context, msg: &Message, _: Args) -> Result<(), Comma
 
     Ok(())
 }
+
+/// Re-runs the permission and role checks done when the bot joins a server.
+pub fn doctor(cmd: CreateCommand) -> CreateCommand {
+    cmd.batch_known_as(&["checkup", "diagnose"])
+        .desc("Checks the bot's permissions and role position, and lists anything that needs fixing.")
+        .help_available(true)
+        .required_permissions(Permissions::MANAGE_GUILD)
+        .usage("")
+        .example("")
+        .max_args(0)
+        .exec(doctor_exec)
+}
+
+pub fn doctor_exec(_: &mut Context, msg: &Message, _: Args) -> Result<(), CommandError> {
+    let connection = utils::get_connection_or_panic();
+
+    let guild = utils::get_guild_result(msg)?;
+    let guild = guild.read();
+
+    let self_id = CACHE.read().user.id;
+
+    let (record, problems) =
+        actions::setup::check_guild_with_record(&guild, self_id, &connection)
+            .map_err(|e| CommandError(format!("Could not check the server due to {}", e)))?;
+
+    let settings = record.settings();
+    let prefixes = settings.active_prefixes();
+    let checklist = actions::setup::generate_checklist(&guild.name, &problems, &prefixes[0]);
+
+    let reply = msg.channel_id.send_message(|m| m.content(checklist))?;
+    let cleanup_delay = settings.cleanup_delay;
+
+    delay_delete!(reply; cleanup_delay);
+
+    Ok(())
+}
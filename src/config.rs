@@ -10,6 +10,8 @@ pub struct Config {
     pub database: DatabaseConfig,
     pub discord: DiscordConfig,
     pub server: ServerConfig,
+    pub debug: Option<DebugConfig>,
+    pub retention: Option<RetentionConfig>,
 }
 
 #[derive(Deserialize)]
@@ -43,6 +45,18 @@ pub struct ServerConfig {
     pub key: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub struct DebugConfig {
+    /// When set, every colour list image is also written into this directory.
+    pub image_dir: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct RetentionConfig {
+    /// Days to keep a guild's colours after the bot is removed, so a re-invite restores them.
+    pub purge_grace_days: Option<u64>,
+}
+
 pub fn get_config_from_file() -> Result<Config, Error> {
     let path = var("COLOUR_BOT_CONFIG")
         .or_else(|_| var("COLOR_BOT_CONFIG"))
@@ -1,5 +1,9 @@
 pub mod commands {
-    /// This defines the accepted amount of distance between two similar strings when fuzzy searching a list of strings.
+    /// Prefixes the bot answers to when a guild hasn't set any of its own.
+    pub const DEFAULT_PREFIXES: [&str; 5] = ["!c", "!colour", "!color", "!colours", "!colors"];
+
+    /// The default amount of edits a six letter name can be off by when fuzzy searching, longer names get more.
+    /// Guilds can change this with the `fuzzy_tolerance` setting.
     pub const MAX_STRING_COMPARE_DELTA: usize = 2;
 
     pub mod roles_edit {
@@ -10,6 +14,46 @@ pub mod commands {
     }
 }
 
+pub mod collector {
+    /// Threads serenity hands events out to, up from its default of 5.
+    /// Every prompt waiting on a reply holds one of them until it's answered.
+    pub const EVENT_THREADS: usize = 16;
+
+    /// Prompts that can wait on a reply at once, leaving the other event threads free to deliver the replies.
+    pub const MAX_LISTENERS: usize = 10;
+}
+
+pub mod cycles {
+    /// The shortest time, in seconds, between colour changes. Anything faster runs into discord's rate limits.
+    pub const MIN_CYCLE_INTERVAL: u64 = 5 * 60;
+
+    /// The longest time, in seconds, between colour changes.
+    pub const MAX_CYCLE_INTERVAL: u64 = 30 * 24 * 60 * 60;
+
+    /// How many colours a single cycle can rotate through.
+    pub const MAX_CYCLE_COLOURS: usize = 20;
+
+    /// How often, in seconds, cycles are checked for a colour change.
+    pub const CYCLE_CHECK_INTERVAL: u64 = 30;
+}
+
+pub mod guilds {
+    /// How long a guild's records are kept after the bot is removed, unless set in the config.
+    pub const DEFAULT_PURGE_GRACE_DAYS: u64 = 30;
+
+    /// How often, in seconds, guilds past their grace period are looked for.
+    pub const PURGE_CHECK_INTERVAL: u64 = 60 * 60;
+}
+
+pub mod temporary {
+    /// The shortest and longest time, in seconds, a temporary colour can be given for.
+    pub const MIN_TEMPORARY_DURATION: u64 = 60;
+    pub const MAX_TEMPORARY_DURATION: u64 = 30 * 24 * 60 * 60;
+
+    /// How often, in seconds, temporary colours are checked for expiry.
+    pub const EXPIRY_CHECK_INTERVAL: u64 = 30;
+}
+
 pub mod webserver {
     // pub const DISCORD_API_URL: &str = "https://discordapp.com/api/v6";
     // unused, but a reference for what the macro should be.
@@ -32,3 +32,4 @@ impl DB {
 
 pub mod models;
 pub mod schema;
+pub mod settings;
@@ -1,20 +1,11 @@
 use diesel::{Identifiable, Insertable, Queryable};
-use std::default::Default;
 
-use serde_json::Map;
 use serde_json::Value;
 
 use bigdecimal::BigDecimal;
 use db::schema::*;
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct GuildSettings {}
-
-impl Default for GuildSettings {
-    fn default() -> Self {
-        GuildSettings {}
-    }
-}
+pub use db::settings::GuildSettings;
 
 #[derive(Queryable, Insertable, Identifiable, Debug, Clone)]
 #[table_name = "guilds"]
@@ -23,6 +14,8 @@ pub struct Guild {
     pub channel_id: Option<BigDecimal>,
     pub settings: Value,
     pub legacy: Option<bool>,
+    pub list_message_ids: Vec<BigDecimal>,
+    pub purge_at: Option<i64>,
 }
 
 impl Guild {
@@ -30,13 +23,15 @@ impl Guild {
         Guild {
             id,
             channel_id: None,
-            settings: Value::Object(Map::new()),
+            settings: GuildSettings::default().to_value(),
             legacy: Some(false),
+            list_message_ids: vec![],
+            purge_at: None,
         }
     }
 
     pub fn settings(&self) -> GuildSettings {
-        GuildSettings {}
+        GuildSettings::from_value(&self.settings)
     }
 }
 
@@ -48,3 +43,104 @@ pub struct Colour {
     pub name: String,
     pub guild_id: BigDecimal,
 }
+
+/// Another name a colour can be found by.
+#[derive(Identifiable, Queryable, Associations, Insertable, Debug, Clone, PartialEq)]
+#[belongs_to(Colour)]
+#[primary_key(colour_id, name)]
+#[table_name = "colour_aliases"]
+pub struct ColourAlias {
+    pub colour_id: BigDecimal,
+    pub name: String,
+}
+
+/// What a member needs before they can pick a colour, see `actions::requirements`.
+#[derive(Identifiable, Queryable, Associations, Insertable, Debug, Clone, PartialEq)]
+#[belongs_to(Colour)]
+#[primary_key(colour_id)]
+#[table_name = "colour_requirements"]
+pub struct ColourRequirement {
+    pub colour_id: BigDecimal,
+    /// Any one of these roles is enough.
+    pub role_ids: Vec<BigDecimal>,
+    pub boosters_only: bool,
+    pub min_days: i32,
+}
+
+impl ColourRequirement {
+    /// A requirement that lets everyone through.
+    pub fn none_for(colour_id: BigDecimal) -> Self {
+        ColourRequirement {
+            colour_id,
+            role_ids: vec![],
+            boosters_only: false,
+            min_days: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.role_ids.is_empty() && !self.boosters_only && self.min_days <= 0
+    }
+}
+
+/// A message in the colour channel that gives out colours through reactions.
+#[derive(Identifiable, Queryable, Associations, Insertable, Debug, Clone, PartialEq)]
+#[belongs_to(Guild)]
+#[table_name = "picker_messages"]
+pub struct PickerMessage {
+    pub id: BigDecimal,
+    pub guild_id: BigDecimal,
+}
+
+/// Binds an emoji on a picker message to a colour.
+#[derive(Identifiable, Queryable, Associations, Insertable, Debug, Clone, PartialEq)]
+#[belongs_to(Colour)]
+#[belongs_to(PickerMessage, foreign_key = "message_id")]
+#[primary_key(colour_id)]
+#[table_name = "colour_reactions"]
+pub struct ColourReaction {
+    pub colour_id: BigDecimal,
+    pub message_id: BigDecimal,
+    pub emoji: String,
+}
+
+/// A user or role rotating through a set of colours, see `actions::cycles`.
+#[derive(Identifiable, Queryable, Associations, Debug, Clone, PartialEq)]
+#[belongs_to(Guild)]
+#[table_name = "cycles"]
+pub struct Cycle {
+    pub id: i32,
+    pub guild_id: BigDecimal,
+    pub user_id: Option<BigDecimal>,
+    pub role_id: Option<BigDecimal>,
+    pub colour_ids: Vec<BigDecimal>,
+    pub interval_seconds: i64,
+    pub position: i32,
+    pub next_run: i64,
+}
+
+/// A cycle that hasn't been saved yet, the database hands out the id.
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "cycles"]
+pub struct NewCycle {
+    pub guild_id: BigDecimal,
+    pub user_id: Option<BigDecimal>,
+    pub role_id: Option<BigDecimal>,
+    pub colour_ids: Vec<BigDecimal>,
+    pub interval_seconds: i64,
+    pub next_run: i64,
+}
+
+/// A colour a member has for a limited time, see `actions::temporary`.
+#[derive(Identifiable, Queryable, Associations, Insertable, Debug, Clone, PartialEq)]
+#[belongs_to(Guild)]
+#[belongs_to(Colour)]
+#[primary_key(guild_id, user_id)]
+#[table_name = "temporary_colours"]
+pub struct TemporaryColour {
+    pub guild_id: BigDecimal,
+    pub user_id: BigDecimal,
+    pub colour_id: BigDecimal,
+    pub previous_colour_ids: Vec<BigDecimal>,
+    pub expires_at: i64,
+}
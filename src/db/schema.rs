@@ -1,3 +1,27 @@
+table! {
+    colour_aliases (colour_id, name) {
+        colour_id -> Numeric,
+        name -> Text,
+    }
+}
+
+table! {
+    colour_reactions (colour_id) {
+        colour_id -> Numeric,
+        message_id -> Numeric,
+        emoji -> Text,
+    }
+}
+
+table! {
+    colour_requirements (colour_id) {
+        colour_id -> Numeric,
+        role_ids -> Array<Numeric>,
+        boosters_only -> Bool,
+        min_days -> Int4,
+    }
+}
+
 table! {
     colours (id) {
         id -> Numeric,
@@ -6,15 +30,64 @@ table! {
     }
 }
 
+table! {
+    cycles (id) {
+        id -> Int4,
+        guild_id -> Numeric,
+        user_id -> Nullable<Numeric>,
+        role_id -> Nullable<Numeric>,
+        colour_ids -> Array<Numeric>,
+        interval_seconds -> Int8,
+        position -> Int4,
+        next_run -> Int8,
+    }
+}
+
 table! {
     guilds (id) {
         id -> Numeric,
         channel_id -> Nullable<Numeric>,
         settings -> Jsonb,
         legacy -> Nullable<Bool>,
+        list_message_ids -> Array<Numeric>,
+        purge_at -> Nullable<Int8>,
+    }
+}
+
+table! {
+    picker_messages (id) {
+        id -> Numeric,
+        guild_id -> Numeric,
+    }
+}
+
+table! {
+    temporary_colours (guild_id, user_id) {
+        guild_id -> Numeric,
+        user_id -> Numeric,
+        colour_id -> Numeric,
+        previous_colour_ids -> Array<Numeric>,
+        expires_at -> Int8,
     }
 }
 
+joinable!(colour_aliases -> colours (colour_id));
+joinable!(colour_reactions -> colours (colour_id));
+joinable!(colour_reactions -> picker_messages (message_id));
+joinable!(colour_requirements -> colours (colour_id));
 joinable!(colours -> guilds (guild_id));
+joinable!(cycles -> guilds (guild_id));
+joinable!(picker_messages -> guilds (guild_id));
+joinable!(temporary_colours -> colours (colour_id));
+joinable!(temporary_colours -> guilds (guild_id));
 
-allow_tables_to_appear_in_same_query!(colours, guilds,);
+allow_tables_to_appear_in_same_query!(
+    colour_aliases,
+    colour_reactions,
+    colour_requirements,
+    colours,
+    cycles,
+    guilds,
+    picker_messages,
+    temporary_colours,
+);
@@ -0,0 +1,688 @@
+use colours::images::{
+    ColourListType, ListTheme, OutputFormat, DEFAULT_FONT_FAMILY, DEFAULT_FONT_SIZE, DEFAULT_PAGE_SIZE,
+    MAX_LIST_COLUMNS,
+};
+use colours::matching::TOLERANCE_SCALE_LENGTH;
+use colours::models::SortMethod;
+use constants::commands::{DEFAULT_PREFIXES, MAX_STRING_COMPARE_DELTA};
+
+use serde_json::{self, Value};
+
+use std::error::Error;
+use std::fmt::{Display, Error as FmtError, Formatter};
+use std::str::FromStr;
+
+/// Current version of the settings blob.
+/// Bump this when a key changes meaning, and handle the upgrade in `GuildSettings::migrate`.
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// Locales the bot has messages for.
+pub const SUPPORTED_LOCALES: [&str; 1] = ["en"];
+
+/// Limits for custom prefixes, so the prefix list stays readable in the help.
+pub const MAX_PREFIXES: usize = 10;
+pub const MAX_PREFIX_LENGTH: usize = 16;
+
+/// Limits for how many colours go on each page of the colour list.
+pub const MIN_PAGE_SIZE: usize = 5;
+pub const MAX_PAGE_SIZE: usize = 200;
+
+/// Limits for the list font, so names stay inside their column.
+pub const MIN_FONT_SIZE: f64 = 12.0;
+pub const MAX_FONT_SIZE: f64 = 72.0;
+pub const MAX_FONT_FAMILY_LENGTH: usize = 64;
+
+/// The most edits a six letter name can be off by, any more and every colour matches every search.
+pub const MAX_FUZZY_TOLERANCE: usize = TOLERANCE_SCALE_LENGTH;
+
+/// Limits for the cleanup delays, every pending delete keeps a thread asleep until it's done.
+pub const MAX_CLEANUP_DELAY: u64 = 300;
+pub const MAX_SWEEP_DELAY: u64 = 300;
+
+/// Every key that can be read or changed through the settings command and the dashboard.
+pub const SETTING_KEYS: [&str; 20] = [
+    "prefixes",
+    "list_style",
+    "sort_method",
+    "page_size",
+    "theme",
+    "columns",
+    "font_family",
+    "font_size",
+    "image_format",
+    "show_hex_codes",
+    "contrast_warnings",
+    "reaction_roles",
+    "fuzzy_tolerance",
+    "ambiguous_match",
+    "colour_codes",
+    "max_colour_distance",
+    "cleanup_delay",
+    "sweep_delay",
+    "booster_role",
+    "locale",
+];
+
+/// How a name that's equally close to several colours gets resolved in the colour channel.
+/// Commands always ask.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AmbiguousMatch {
+    /// Quietly uses the first match by name.
+    PickBest,
+    /// Lists the matches and lets the user pick one.
+    Ask,
+}
+
+impl Display for AmbiguousMatch {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+        match *self {
+            AmbiguousMatch::PickBest => "pick_best",
+            AmbiguousMatch::Ask => "ask",
+        }.fmt(f)
+    }
+}
+
+impl FromStr for AmbiguousMatch {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().replace(' ', "_").as_str() {
+            "pick_best" | "best" => Ok(AmbiguousMatch::PickBest),
+            "ask" => Ok(AmbiguousMatch::Ask),
+            other => Err(format!(
+                "\"{}\" is not an option. Valid options are: pick_best, ask",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SettingsError {
+    UnknownKey(String),
+    InvalidValue(String, String),
+}
+
+impl Display for SettingsError {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), FmtError> {
+        match *self {
+            SettingsError::UnknownKey(ref key) => write!(
+                fmt,
+                "There is no setting called \"{}\". Valid settings are: {}",
+                key,
+                SETTING_KEYS.join(", ")
+            ),
+            SettingsError::InvalidValue(ref key, ref reason) => {
+                write!(fmt, "Invalid value for \"{}\": {}", key, reason)
+            }
+        }
+    }
+}
+
+impl Error for SettingsError {
+    fn description(&self) -> &str {
+        match *self {
+            SettingsError::UnknownKey(_) => "Unknown setting key.",
+            SettingsError::InvalidValue(..) => "Invalid setting value.",
+        }
+    }
+}
+
+/// Typed representation of the `guilds.settings` JSONB column.
+/// Missing keys fall back to their defaults, so older records keep working as new settings are added.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct GuildSettings {
+    pub version: u32,
+    /// Custom command prefixes, the default prefixes are used when this is empty.
+    pub prefixes: Vec<String>,
+    /// The layout used for the colour list image.
+    pub list_style: ColourListType,
+    /// How the colour list image is ordered.
+    pub sort_method: SortMethod,
+    /// How many colours are shown on each image of the colour list.
+    pub page_size: usize,
+    /// The background the list is drawn on.
+    pub theme: ListTheme,
+    /// How many columns the basic list has, each one shows the colours on another theme.
+    pub columns: usize,
+    pub font_family: String,
+    pub font_size: f64,
+    /// The format the list is uploaded to discord in, only formats discord shows as images.
+    pub image_format: OutputFormat,
+    /// Prints each colour's hex code next to its name in the list.
+    pub show_hex_codes: bool,
+    /// Marks colours that are hard to read on the dark or light discord theme.
+    pub contrast_warnings: bool,
+    /// Posts reaction picker messages under the colour list, reacting to one gives that colour.
+    pub reaction_roles: bool,
+    /// How many edits a six letter colour name can be off by and still match, scaled by the name's length.
+    pub fuzzy_tolerance: usize,
+    /// What the colour channel does when several colours are equally close to a name.
+    pub ambiguous_match: AmbiguousMatch,
+    /// Lets users post a hex or rgb code in the colour channel to get the closest colour.
+    pub colour_codes: bool,
+    /// Colour codes further than this from every colour are refused, as a CIEDE2000 distance.
+    pub max_colour_distance: Option<f64>,
+    /// Seconds before the bot's replies in the colour channel are deleted.
+    pub cleanup_delay: u64,
+    /// Seconds before the colour channel is swept of leftover messages.
+    pub sweep_delay: u64,
+    /// The role boosters get, for servers where it isn't called "Server Booster".
+    /// The role is found by name when this is `None`.
+    pub booster_role: Option<u64>,
+    pub locale: String,
+}
+
+impl Default for GuildSettings {
+    fn default() -> Self {
+        GuildSettings {
+            version: SETTINGS_VERSION,
+            prefixes: vec![],
+            list_style: ColourListType::BasicList,
+            sort_method: SortMethod::HSL,
+            page_size: DEFAULT_PAGE_SIZE,
+            theme: ListTheme::Dark,
+            columns: 2,
+            font_family: DEFAULT_FONT_FAMILY.to_string(),
+            font_size: DEFAULT_FONT_SIZE,
+            image_format: OutputFormat::Png,
+            show_hex_codes: false,
+            contrast_warnings: false,
+            reaction_roles: false,
+            fuzzy_tolerance: MAX_STRING_COMPARE_DELTA,
+            ambiguous_match: AmbiguousMatch::Ask,
+            colour_codes: false,
+            max_colour_distance: None,
+            cleanup_delay: 8,
+            sweep_delay: 6,
+            booster_role: None,
+            locale: "en".to_string(),
+        }
+    }
+}
+
+fn parse_value<T: FromStr>(key: &str, value: &str) -> Result<T, SettingsError>
+where
+    T::Err: Display,
+{
+    value
+        .trim()
+        .parse::<T>()
+        .map_err(|e| SettingsError::InvalidValue(key.to_string(), format!("{}", e)))
+}
+
+/// Reads a yes/no setting, accepting the usual ways of writing it.
+fn parse_bool(key: &str, value: &str) -> Result<bool, SettingsError> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "yes" | "on" | "enabled" | "1" => Ok(true),
+        "false" | "no" | "off" | "disabled" | "0" => Ok(false),
+        _ => Err(SettingsError::InvalidValue(
+            key.to_string(),
+            "expected true or false".to_string(),
+        )),
+    }
+}
+
+/// Reads an optional colour distance, `none`, `off` or 0 turns the limit off.
+fn parse_distance(key: &str, value: &str) -> Result<Option<f64>, SettingsError> {
+    match value.trim().to_lowercase().as_str() {
+        "none" | "off" | "disabled" => return Ok(None),
+        _ => (),
+    }
+
+    let distance: f64 = parse_value(key, value)?;
+
+    if !distance.is_finite() || distance < 0.0 {
+        return Err(SettingsError::InvalidValue(
+            key.to_string(),
+            "expected a positive distance, or none".to_string(),
+        ));
+    }
+
+    Ok(if distance == 0.0 { None } else { Some(distance) })
+}
+
+/// Reads a delay in seconds, up to `max`.
+fn parse_delay(key: &str, value: &str, max: u64) -> Result<u64, SettingsError> {
+    let delay: u64 = parse_value(key, value)?;
+
+    if delay > max {
+        return Err(SettingsError::InvalidValue(
+            key.to_string(),
+            format!("the delay can be at most {} seconds", max),
+        ));
+    }
+
+    Ok(delay)
+}
+
+/// Reads a role id or mention, `none` or `auto` goes back to finding the role by name.
+fn parse_role(key: &str, value: &str) -> Result<Option<u64>, SettingsError> {
+    let value = value.trim();
+
+    match value.to_lowercase().as_str() {
+        "none" | "auto" | "off" => return Ok(None),
+        _ => (),
+    }
+
+    let id = if value.starts_with("<@&") && value.ends_with('>') {
+        &value[3..value.len() - 1]
+    } else {
+        value
+    };
+
+    id.parse::<u64>().map(Some).map_err(|_| {
+        SettingsError::InvalidValue(
+            key.to_string(),
+            "expected a role, or none to find it by name".to_string(),
+        )
+    })
+}
+
+/// Splits a space or comma separated list of prefixes, an empty list means the defaults are used.
+fn parse_prefixes(key: &str, value: &str) -> Result<Vec<String>, SettingsError> {
+    let mut prefixes: Vec<String> = vec![];
+
+    for prefix in value.split(|c: char| c == ',' || c.is_whitespace()) {
+        let prefix = prefix.trim().to_lowercase();
+
+        if prefix.is_empty() || prefixes.contains(&prefix) {
+            continue;
+        }
+
+        if prefix.chars().count() > MAX_PREFIX_LENGTH {
+            return Err(SettingsError::InvalidValue(
+                key.to_string(),
+                format!("prefixes can be at most {} characters long", MAX_PREFIX_LENGTH),
+            ));
+        }
+
+        prefixes.push(prefix);
+    }
+
+    if prefixes.len() > MAX_PREFIXES {
+        return Err(SettingsError::InvalidValue(
+            key.to_string(),
+            format!("a server can have at most {} prefixes", MAX_PREFIXES),
+        ));
+    }
+
+    Ok(prefixes)
+}
+
+impl GuildSettings {
+    /// Reads the settings out of the stored JSON, using defaults for anything missing or malformed.
+    /// Keys are merged over the defaults one at a time, so a single key that no longer reads
+    /// doesn't reset the rest of the settings.
+    pub fn from_value(value: &Value) -> Self {
+        let stored = match *value {
+            Value::Object(ref stored) => stored,
+            _ => return GuildSettings::default(),
+        };
+
+        let mut merged = match GuildSettings::default().to_value() {
+            Value::Object(merged) => merged,
+            _ => return GuildSettings::default(),
+        };
+
+        for (key, value) in stored {
+            let previous = merged.insert(key.clone(), value.clone());
+
+            if serde_json::from_value::<GuildSettings>(Value::Object(merged.clone())).is_err() {
+                match previous {
+                    Some(previous) => merged.insert(key.clone(), previous),
+                    None => merged.remove(key),
+                };
+            }
+        }
+
+        serde_json::from_value::<GuildSettings>(Value::Object(merged))
+            .map(GuildSettings::migrate)
+            .unwrap_or_default()
+    }
+
+    /// Turns the settings back into JSON for the `guilds.settings` column.
+    pub fn to_value(&self) -> Value {
+        serde_json::to_value(self).unwrap_or_else(|_| Value::Object(Default::default()))
+    }
+
+    /// Upgrades settings saved by an older version of the bot.
+    fn migrate(self) -> Self {
+        GuildSettings {
+            version: SETTINGS_VERSION,
+            image_format: if self.image_format.previews_in_discord() {
+                self.image_format
+            } else {
+                OutputFormat::Png
+            },
+            fuzzy_tolerance: self.fuzzy_tolerance.min(MAX_FUZZY_TOLERANCE),
+            cleanup_delay: self.cleanup_delay.min(MAX_CLEANUP_DELAY),
+            sweep_delay: self.sweep_delay.min(MAX_SWEEP_DELAY),
+            ..self
+        }
+    }
+
+    /// Changes a single setting from its text representation.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), SettingsError> {
+        match key.trim().to_lowercase().as_str() {
+            "prefixes" => self.prefixes = parse_prefixes(key, value)?,
+            "list_style" => self.list_style = parse_value(key, value)?,
+            "sort_method" => self.sort_method = parse_value(key, value)?,
+            "page_size" => {
+                let size: usize = parse_value(key, value)?;
+
+                if size < MIN_PAGE_SIZE || size > MAX_PAGE_SIZE {
+                    return Err(SettingsError::InvalidValue(
+                        key.to_string(),
+                        format!(
+                            "pages can hold between {} and {} colours",
+                            MIN_PAGE_SIZE, MAX_PAGE_SIZE
+                        ),
+                    ));
+                }
+
+                self.page_size = size;
+            }
+            "theme" => self.theme = parse_value(key, value)?,
+            "columns" => {
+                let columns: usize = parse_value(key, value)?;
+
+                if columns < 1 || columns > MAX_LIST_COLUMNS {
+                    return Err(SettingsError::InvalidValue(
+                        key.to_string(),
+                        format!("the list can have between 1 and {} columns", MAX_LIST_COLUMNS),
+                    ));
+                }
+
+                self.columns = columns;
+            }
+            "font_family" => {
+                let family = value.trim();
+
+                // the family goes straight into the svg, so only allow what font names are made of.
+                let is_valid = !family.is_empty()
+                    && family.len() <= MAX_FONT_FAMILY_LENGTH
+                    && family
+                        .chars()
+                        .all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == ',');
+
+                if !is_valid {
+                    return Err(SettingsError::InvalidValue(
+                        key.to_string(),
+                        format!(
+                            "font names can only have letters, numbers, spaces, dashes and commas, up to {} characters",
+                            MAX_FONT_FAMILY_LENGTH
+                        ),
+                    ));
+                }
+
+                self.font_family = family.to_string();
+            }
+            "font_size" => {
+                let size: f64 = parse_value(key, value)?;
+
+                if !(size >= MIN_FONT_SIZE && size <= MAX_FONT_SIZE) {
+                    return Err(SettingsError::InvalidValue(
+                        key.to_string(),
+                        format!("the font size has to be between {} and {}", MIN_FONT_SIZE, MAX_FONT_SIZE),
+                    ));
+                }
+
+                self.font_size = size;
+            }
+            "image_format" => {
+                let format: OutputFormat = parse_value(key, value)?;
+
+                if !format.previews_in_discord() {
+                    return Err(SettingsError::InvalidValue(
+                        key.to_string(),
+                        "discord only shows png and webp lists, svg lists can be downloaded from the dashboard".to_string(),
+                    ));
+                }
+
+                self.image_format = format;
+            }
+            "show_hex_codes" => self.show_hex_codes = parse_bool(key, value)?,
+            "contrast_warnings" => self.contrast_warnings = parse_bool(key, value)?,
+            "reaction_roles" => self.reaction_roles = parse_bool(key, value)?,
+            "fuzzy_tolerance" => {
+                let tolerance: usize = parse_value(key, value)?;
+
+                if tolerance > MAX_FUZZY_TOLERANCE {
+                    return Err(SettingsError::InvalidValue(
+                        key.to_string(),
+                        format!("the tolerance can be between 0 and {}", MAX_FUZZY_TOLERANCE),
+                    ));
+                }
+
+                self.fuzzy_tolerance = tolerance;
+            }
+            "ambiguous_match" => self.ambiguous_match = parse_value(key, value)?,
+            "colour_codes" => self.colour_codes = parse_bool(key, value)?,
+            "max_colour_distance" => self.max_colour_distance = parse_distance(key, value)?,
+            "cleanup_delay" => self.cleanup_delay = parse_delay(key, value, MAX_CLEANUP_DELAY)?,
+            "sweep_delay" => self.sweep_delay = parse_delay(key, value, MAX_SWEEP_DELAY)?,
+            "booster_role" => self.booster_role = parse_role(key, value)?,
+            "locale" => {
+                let locale = value.trim().to_lowercase();
+
+                if !SUPPORTED_LOCALES.contains(&locale.as_str()) {
+                    return Err(SettingsError::InvalidValue(
+                        key.to_string(),
+                        format!("supported locales are {}", SUPPORTED_LOCALES.join(", ")),
+                    ));
+                }
+
+                self.locale = locale;
+            }
+            other => return Err(SettingsError::UnknownKey(other.to_string())),
+        }
+
+        Ok(())
+    }
+
+    /// Sets a single setting back to its default value.
+    pub fn reset(&mut self, key: &str) -> Result<(), SettingsError> {
+        let default = GuildSettings::default();
+        let value = default
+            .get(key)
+            .ok_or_else(|| SettingsError::UnknownKey(key.to_string()))?;
+
+        self.set(key, &value)
+    }
+
+    /// Gets the text representation of a single setting.
+    pub fn get(&self, key: &str) -> Option<String> {
+        let value = match key.trim().to_lowercase().as_str() {
+            "prefixes" => self.prefixes.join(" "),
+            "list_style" => self.list_style.to_string(),
+            "sort_method" => self.sort_method.to_string(),
+            "page_size" => self.page_size.to_string(),
+            "theme" => self.theme.to_string(),
+            "columns" => self.columns.to_string(),
+            "font_family" => self.font_family.clone(),
+            "font_size" => self.font_size.to_string(),
+            "image_format" => self.image_format.to_string(),
+            "show_hex_codes" => self.show_hex_codes.to_string(),
+            "contrast_warnings" => self.contrast_warnings.to_string(),
+            "reaction_roles" => self.reaction_roles.to_string(),
+            "fuzzy_tolerance" => self.fuzzy_tolerance.to_string(),
+            "ambiguous_match" => self.ambiguous_match.to_string(),
+            "colour_codes" => self.colour_codes.to_string(),
+            "max_colour_distance" => self.max_colour_distance.map(|d| d.to_string()).unwrap_or_else(|| "none".to_string()),
+            "cleanup_delay" => self.cleanup_delay.to_string(),
+            "sweep_delay" => self.sweep_delay.to_string(),
+            "booster_role" => self.booster_role.map(|id| id.to_string()).unwrap_or_else(|| "none".to_string()),
+            "locale" => self.locale.clone(),
+            _ => return None,
+        };
+
+        Some(value)
+    }
+
+    /// The prefixes the guild answers to, falling back to `DEFAULT_PREFIXES`.
+    pub fn active_prefixes(&self) -> Vec<String> {
+        if self.prefixes.is_empty() {
+            DEFAULT_PREFIXES.iter().map(|p| p.to_string()).collect()
+        } else {
+            self.prefixes.clone()
+        }
+    }
+
+    /// All the settings as `(key, value)` pairs, in the order of `SETTING_KEYS`.
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        SETTING_KEYS
+            .iter()
+            .filter_map(|key| self.get(key).map(|value| (*key, value)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn missing_keys_use_defaults() {
+        let value: Value = serde_json::from_str(r#"{ "fuzzy_tolerance": 4 }"#).unwrap();
+
+        let settings = GuildSettings::from_value(&value);
+
+        assert_eq!(settings.fuzzy_tolerance, 4);
+        assert_eq!(
+            settings,
+            GuildSettings {
+                fuzzy_tolerance: 4,
+                ..GuildSettings::default()
+            }
+        );
+    }
+
+    #[test]
+    pub fn malformed_settings_use_defaults() {
+        let settings = GuildSettings::from_value(&Value::Null);
+
+        assert_eq!(settings, GuildSettings::default());
+    }
+
+    #[test]
+    pub fn unreadable_keys_keep_the_other_settings() {
+        let value: Value = serde_json::from_str(
+            r#"{ "fuzzy_tolerance": 4, "sort_method": "not_a_method", "cleanup_delay": 100000 }"#,
+        ).unwrap();
+
+        let settings = GuildSettings::from_value(&value);
+
+        assert_eq!(settings.fuzzy_tolerance, 4);
+        assert_eq!(settings.sort_method, GuildSettings::default().sort_method);
+        assert_eq!(settings.cleanup_delay, MAX_CLEANUP_DELAY);
+
+        let value: Value = serde_json::from_str(r#"{ "fuzzy_tolerance": 100 }"#).unwrap();
+        assert_eq!(GuildSettings::from_value(&value).fuzzy_tolerance, MAX_FUZZY_TOLERANCE);
+    }
+
+    #[test]
+    pub fn stored_svg_lists_fall_back_to_png() {
+        let value: Value = serde_json::from_str(r#"{ "image_format": "svg" }"#).unwrap();
+
+        assert_eq!(GuildSettings::from_value(&value).image_format, OutputFormat::Png);
+    }
+
+    #[test]
+    pub fn settings_survive_a_round_trip() {
+        let mut settings = GuildSettings::default();
+        settings.cleanup_delay = 20;
+
+        let value = settings.to_value();
+
+        assert_eq!(GuildSettings::from_value(&value), settings);
+    }
+
+    #[test]
+    pub fn can_set_and_reset_keys() {
+        let mut settings = GuildSettings::default();
+
+        settings.set("sweep_delay", "12").unwrap();
+        assert_eq!(settings.sweep_delay, 12);
+
+        settings.set("show_hex_codes", "on").unwrap();
+        assert!(settings.show_hex_codes);
+
+        settings.set("ambiguous_match", "pick best").unwrap();
+        assert_eq!(settings.ambiguous_match, AmbiguousMatch::PickBest);
+
+        settings.set("max_colour_distance", "12.5").unwrap();
+        assert_eq!(settings.max_colour_distance, Some(12.5));
+
+        settings.reset("max_colour_distance").unwrap();
+        assert_eq!(settings.max_colour_distance, None);
+
+        settings.reset("sweep_delay").unwrap();
+        assert_eq!(settings.sweep_delay, GuildSettings::default().sweep_delay);
+    }
+
+    #[test]
+    pub fn bad_keys_and_values_fail() {
+        let mut settings = GuildSettings::default();
+
+        assert!(settings.set("not_a_key", "1").is_err());
+        assert!(settings.set("fuzzy_tolerance", "lots").is_err());
+        assert!(settings.set("fuzzy_tolerance", "18446744073709551615").is_err());
+        assert!(settings.set("locale", "xx").is_err());
+        assert!(settings.set("page_size", "1").is_err());
+        assert!(settings.set("contrast_warnings", "maybe").is_err());
+        assert!(settings.set("prefixes", "!averyveryverylongprefix").is_err());
+        assert!(settings.set("columns", "4").is_err());
+        assert!(settings.set("font_family", "Roboto\"/><script").is_err());
+        assert!(settings.set("theme", "not a colour").is_err());
+        assert!(settings.set("image_format", "svg").is_err());
+        assert!(settings.set("cleanup_delay", "100000").is_err());
+        assert!(settings.set("sweep_delay", "-1").is_err());
+        assert!(settings.set("booster_role", "Boosters").is_err());
+    }
+
+    #[test]
+    pub fn booster_roles_can_be_ids_or_mentions() {
+        let mut settings = GuildSettings::default();
+
+        settings.set("booster_role", "484529706037805056").unwrap();
+        assert_eq!(settings.booster_role, Some(484529706037805056));
+
+        settings.set("booster_role", "<@&483501321945612319>").unwrap();
+        assert_eq!(settings.booster_role, Some(483501321945612319));
+        assert_eq!(settings.get("booster_role").unwrap(), "483501321945612319");
+
+        settings.reset("booster_role").unwrap();
+        assert_eq!(settings.booster_role, None);
+    }
+
+    #[test]
+    pub fn themes_can_be_custom_colours() {
+        let mut settings = GuildSettings::default();
+
+        settings.set("theme", "amoled").unwrap();
+        assert_eq!(settings.theme, ListTheme::Amoled);
+
+        settings.set("theme", "#202225").unwrap();
+        assert_eq!(settings.theme, ListTheme::Custom("#202225".to_string()));
+        assert_eq!(settings.get("theme").unwrap(), "#202225");
+
+        let value = settings.to_value();
+        assert_eq!(GuildSettings::from_value(&value).theme, settings.theme);
+    }
+
+    #[test]
+    pub fn prefixes_fall_back_to_defaults() {
+        let mut settings = GuildSettings::default();
+
+        assert_eq!(settings.active_prefixes().len(), DEFAULT_PREFIXES.len());
+
+        settings.set("prefixes", "?c, ?colour ?c").unwrap();
+        assert_eq!(settings.active_prefixes(), vec!["?c", "?colour"]);
+
+        settings.reset("prefixes").unwrap();
+        assert!(settings.prefixes.is_empty());
+    }
+}
@@ -3,3 +3,16 @@
 
 pub const GREEN_TICK: &str = "✅";
 pub const RED_CROSS: &str = "❌";
+
+/// Keycap numbers, used to pick from a numbered list of options.
+pub const NUMBERS: [&str; 9] = [
+    "1\u{20e3}",
+    "2\u{20e3}",
+    "3\u{20e3}",
+    "4\u{20e3}",
+    "5\u{20e3}",
+    "6\u{20e3}",
+    "7\u{20e3}",
+    "8\u{20e3}",
+    "9\u{20e3}",
+];
@@ -5,6 +5,7 @@
 // FIXME: Warn/deny for this once -DIESEL- updates for this warning.
 #![allow(proc_macro_derive_resolution_fallback, unknown_lints)]
 
+extern crate base64;
 extern crate bigdecimal;
 extern crate cairo;
 extern crate hsl;
@@ -47,9 +48,11 @@ extern crate lazy_static;
 extern crate chashmap;
 extern crate serenity;
 extern crate openssl;
+extern crate palette;
 extern crate svg;
 extern crate toml;
 extern crate typemap;
+extern crate webp;
 
 // needs to resolve before other modules
 #[macro_use]
@@ -57,10 +60,10 @@ mod macros;
 mod config;
 mod constants;
 
-use serenity::model::id::MessageId;
+use serenity::model::id::{MessageId, RoleId};
 
 lazy_static! {
-    // static ref COLLECTOR: Collector = { Collector::new() };
+    pub static ref COLLECTOR: collector::Collector = collector::Collector::new();
 
     pub static ref CONFIG: config::Config = config::get_config_from_file()
         .expect("Could not find a config file. Either provide a config.toml at the root or set a env key called COLOUR_BOT_CONFIG as a path to a config.");
@@ -72,6 +75,9 @@ lazy_static! {
     };
 
     pub static ref CLEANER: chashmap::CHashMap<MessageId, ()> = chashmap::CHashMap::new();
+
+    /// The name and colour the bot last gave a role, so the update event it causes can be told apart from edits made in discord.
+    pub static ref ROLE_EDITS: chashmap::CHashMap<RoleId, (String, u32)> = chashmap::CHashMap::new();
 }
 
 mod actions;
@@ -86,7 +92,9 @@ mod utils;
 mod webserver;
 
 use cleaner::Cleaner;
+use collector::CollectorValue;
 
+use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 
@@ -95,15 +103,21 @@ use serenity::framework::standard::help_commands::with_embeds;
 use serenity::framework::standard::Args;
 use serenity::framework::standard::{CommandError, DispatchError};
 use serenity::framework::StandardFramework;
-use serenity::model::channel::{Channel, Message};
+use serenity::model::channel::{Channel, GuildChannel, Message, Reaction};
 use serenity::model::gateway::Ready;
-use serenity::model::id::ChannelId;
-use serenity::prelude::Context;
+use serenity::model::guild::{Guild, PartialGuild, Role};
+use serenity::model::id::{ChannelId, GuildId, RoleId};
+use serenity::prelude::{Context, RwLock};
 use serenity::Client;
 
 use num_traits::ToPrimitive;
 
-const PREFIX_LIST: [&str; 5] = ["!c", "!colour", "!color", "!colours", "!colors"];
+use constants::collector::EVENT_THREADS;
+use constants::commands::DEFAULT_PREFIXES;
+use constants::cycles::CYCLE_CHECK_INTERVAL;
+use constants::guilds::PURGE_CHECK_INTERVAL;
+use constants::temporary::EXPIRY_CHECK_INTERVAL;
+
 const HELP_CMD_NAME: &str = "help";
 
 // TODO: !! UPDATE THE COLOUR LIST WHEN COLOURS CHANGE.
@@ -113,43 +127,82 @@ const HELP_CMD_NAME: &str = "help";
 struct Handler;
 
 impl EventHandler for Handler {
-    // TODO: impl channel delete check
-    // TODO: impl guild join setup and permissions check
+    /// Gives out the colour bound to a reaction on a picker message.
+    fn reaction_add(&self, _: Context, reaction: Reaction) {
+        if reaction.user_id == serenity::utils::with_cache(|cache| cache.user.id) {
+            return;
+        }
+
+        if COLLECTOR.emit(CollectorValue::Reaction(reaction.clone())) {
+            return;
+        }
+
+        let connection = utils::get_connection_or_panic();
+        let _ = actions::pickers::pick_colour_from_reaction(&reaction, &connection);
+    }
+
+    /// Takes away the colour bound to a reaction on a picker message.
+    fn reaction_remove(&self, _: Context, reaction: Reaction) {
+        if reaction.user_id == serenity::utils::with_cache(|cache| cache.user.id) {
+            return;
+        }
+
+        let connection = utils::get_connection_or_panic();
+        let _ = actions::pickers::clear_colour_from_reaction(&reaction, &connection);
+    }
+
+    /// Stops tracking the colour channel when it gets deleted, and lets the admins know.
+    fn channel_delete(&self, _: Context, channel: Arc<RwLock<GuildChannel>>) {
+        let (channel_id, guild_id) = {
+            let channel = channel.read();
+            (channel.id, channel.guild_id)
+        };
+
+        let connection = utils::get_connection_or_panic();
+
+        if let Ok(Some(record)) = actions::guilds::clear_deleted_channel(channel_id, &connection) {
+            if let Some(guild) = guild_id.find() {
+                let guild = guild.read();
+                let prefixes = record.settings().active_prefixes();
+                let message =
+                    actions::setup::generate_channel_deleted_message(&guild.name, &prefixes[0]);
+
+                let _ = actions::setup::notify_admins(&guild, &message);
+            }
+        }
+    }
 
     /// Message handler,
     /// should be managing the colour channel and the cleaning of the channel.
     fn message(&self, mut ctx: Context, message: Message) {
-        let starts_with_prefix = PREFIX_LIST
-            .iter()
-            .map(|string| string.to_string().to_lowercase().clone())
-            .map(|prefix| message.content.to_lowercase().starts_with(&prefix))
-            .any(|id| id);
-
         if message.author.bot {
             return;
         }
-        // thread::spawn(move || {
-        //     println!("about to lock ");
-        //     let mut coll = COLLECTOR.0.lock().unwrap();
-
-        //     let computation = coll.emit_value(
-        //         CollectorItem::Message,
-        //         CollectorValue::Message(emitted_message),
-        //     );
-        //     computation
-        //         .wait()
-        //         .expect("Error emitting message to Collector");
-        //     println!("done lock");
-        // });
+
+        // replies to a command waiting on the collector shouldn't be read as colours or commands.
+        if COLLECTOR.emit(CollectorValue::Message(message.clone())) {
+            return;
+        }
 
         let connection = utils::get_connection_or_panic();
 
-        let colour_channel_inner_opt = utils::get_guild_result(&message)
+        let guild_record_opt = utils::get_guild_result(&message)
             .ok()
             .and_then(|guild| {
                 let id = guild.read().id;
                 actions::guilds::convert_guild_to_record(id, &connection)
-            }).and_then(|guild_record| guild_record.channel_id)
+            });
+
+        let settings = guild_record_opt
+            .as_ref()
+            .map(|guild_record| guild_record.settings())
+            .unwrap_or_default();
+
+        let starts_with_prefix =
+            actions::guilds::match_prefix(&message.content, &settings.active_prefixes()).is_some();
+
+        let colour_channel_inner_opt = guild_record_opt
+            .and_then(|guild_record| guild_record.channel_id)
             .and_then(|id| id.to_u64());
 
         let channel_id = message.channel_id;
@@ -167,6 +220,7 @@ impl EventHandler for Handler {
                     let result = commands::roles::get_colour_exec(&mut ctx, &message, args);
 
                     let message_clone = message.clone();
+                    let cleanup_delay = settings.cleanup_delay;
 
                     let _ = result
                         .map(|_| {
@@ -178,19 +232,21 @@ impl EventHandler for Handler {
                                 .send_message(|msg| {
                                     msg.content(format!("Couldn't assign a colour due to: {}", m))
                                 }).map(|msg| {
-                                    delay_delete!(msg; 8);
+                                    delay_delete!(msg; cleanup_delay);
                                 });
                         });
                 }
 
-                // cleaner procedure, will execute 6 seconds after the message event ends.
+                let sweep_delay = settings.sweep_delay;
+
+                // cleaner procedure, will execute `sweep_delay` seconds (6 by default) after the message event ends.
                 // ?HACKY? relies on the .before method of the framework to be called before this does
                 //       ? will .before be called sync? basically a library implmentation detail, should look into it.
                 // due to having no access to commands that are invoked with the right prefix but have no way
                 // to check if they're legit commands or not.
                 // Advantages of this sweep approach is that bot messages and other anomalies in the channels will be purged.
                 thread::spawn(move || {
-                    thread::sleep(Duration::from_secs(6));
+                    thread::sleep(Duration::from_secs(sweep_delay));
                     let colour_channel = ChannelId(channel_id_inner);
                     // collect a few messages and verify if they're commands or not.
                     // otherwise they're just loiting the channel and will be PURGED
@@ -230,6 +286,59 @@ impl EventHandler for Handler {
         }
     }
 
+    /// Sets up the record for newly joined guilds, then sends the owner a checklist of anything the bot is missing.
+    fn guild_create(&self, _: Context, guild: Guild, is_new: bool) {
+        let connection = utils::get_connection_or_panic();
+
+        // rejoining within the grace period keeps the old colours.
+        let _ = actions::guilds::cancel_purge(guild.id, &connection);
+
+        if !is_new {
+            return;
+        }
+
+        let self_id = serenity::utils::with_cache(|cache| cache.user.id);
+
+        if let Ok((record, problems)) =
+            actions::setup::check_guild_with_record(&guild, self_id, &connection)
+        {
+            let prefixes = record.settings().active_prefixes();
+            let checklist =
+                actions::setup::generate_checklist(&guild.name, &problems, &prefixes[0]);
+
+            let _ = actions::setup::notify_admins(&guild, &checklist);
+        }
+    }
+
+    /// Schedules the guild's records to be purged once the grace period is over.
+    /// Outages also end up here, but the guild comes back through `guild_create` which cancels the purge.
+    fn guild_delete(&self, _: Context, guild: PartialGuild, _: Option<Arc<RwLock<Guild>>>) {
+        let connection = utils::get_connection_or_panic();
+
+        let grace_period = (actions::guilds::purge_grace_days() * 24 * 60 * 60) as i64;
+        let purge_at = utils::unix_timestamp() + grace_period;
+
+        let _ = actions::guilds::schedule_purge(guild.id, purge_at, &connection);
+    }
+
+    /// Drops the colour when its role gets deleted outside of the bot.
+    fn guild_role_delete(&self, _: Context, guild_id: GuildId, role_id: RoleId, _: Option<Role>) {
+        let connection = utils::get_connection_or_panic();
+
+        if actions::colours::remove_deleted_role(guild_id, role_id, &connection).is_some() {
+            let _ = actions::guilds::refresh_channel_message(guild_id, &connection);
+        }
+    }
+
+    /// Picks up colour and name changes made in discord's role settings.
+    fn guild_role_update(&self, _: Context, guild_id: GuildId, old: Option<Role>, new: Role) {
+        let connection = utils::get_connection_or_panic();
+
+        if let Ok(Some(_)) = actions::colours::sync_role_update(old.as_ref(), &new, &connection) {
+            let _ = actions::guilds::refresh_channel_message(guild_id, &connection);
+        }
+    }
+
     fn ready(&self, _: Context, ready: Ready) {
         println!(
             "Bot is now running!\nOn {} gulids, named as {}.",
@@ -244,7 +353,17 @@ fn create_framework() -> StandardFramework {
 
     framework
         .configure(|cfg| {
-            cfg.prefixes(&PREFIX_LIST)
+            cfg.prefixes(&DEFAULT_PREFIXES)
+                .dynamic_prefix(|_, msg| {
+                    let guild_id = msg.guild_id?;
+                    let connection = DB.make_connection()?;
+                    let prefixes = actions::guilds::get_prefixes_for_guild(guild_id, &connection);
+
+                    // handing back a prefix that doesn't match stops the framework
+                    // from falling back to the default prefixes for this guild.
+                    actions::guilds::match_prefix(&msg.content, &prefixes)
+                        .or_else(|| prefixes.first().cloned())
+                })
                 .ignore_bots(true)
                 .on_mention(true)
                 .allow_whitespace(true)
@@ -254,21 +373,34 @@ fn create_framework() -> StandardFramework {
         .group("colours", |group| {
             group
                 .command("get", commands::roles::get_colour)
+                .command("grant", commands::roles::grant_colour)
                 .command("add", commands::roles::add_colour)
+                .command("adopt", commands::roles::adopt_colours)
                 .command("remove", commands::roles::remove_colour)
                 .command("generate", commands::roles::generate_colour)
                 .command("edit", commands::roles::edit_colour)
                 .command("list", commands::lists::list_colours)
                 .command("clear", commands::roles::clear_colours)
-            // .command("cycle", commands::roles::cycle_colours)
+                .command("alias", commands::aliases::alias)
+                .command("require", commands::requirements::require)
+                .command("cycle", commands::cycles::cycle)
+                .command("export", commands::palettes::export_colours)
+                .command("import", commands::palettes::import_colours)
         })
         .group("channel", |group| {
             group
                 .command("setchannel", commands::channels::set_channel)
                 .command("refreshchannel", commands::lists::refresh_list)
         })
+        .group("settings", |group| {
+            group
+                .command("settings", commands::settings::settings)
+                .command("prefix", commands::settings::prefix)
+        })
         .group("utils", |group| {
-            group.command("info", commands::utils::info)
+            group
+                .command("info", commands::utils::info)
+                .command("doctor", commands::utils::doctor)
         })
         .before(|_, msg, name| {
             // culling help messages because they can flood the chat and dont delete themselves,
@@ -379,6 +511,9 @@ fn main() {
     let mut client = Client::new(&CONFIG.discord.token, Handler)
         .expect("Could not initiate client. Check if your token is a *VALID* bot token.");
 
+    // commands waiting on a reply block their event thread, so there need to be enough left to deliver it.
+    client.threadpool.set_num_threads(EVENT_THREADS);
+
     {
         let mut data = client.data.lock();
         data.insert::<Cleaner>(Cleaner::new());
@@ -430,6 +565,34 @@ use parking_lot::deadlock;
             webserver::server::create_server();
         });
 
+        scope.spawn(|| loop {
+            {
+                let connection = utils::get_connection_or_panic();
+                let _ = actions::guilds::purge_expired_guilds(utils::unix_timestamp(), &connection);
+            }
+
+            thread::sleep(Duration::from_secs(PURGE_CHECK_INTERVAL));
+        });
+
+        // cycles live in the database, so any that were due while the bot was down run on the first check.
+        scope.spawn(|| loop {
+            {
+                let connection = utils::get_connection_or_panic();
+                let _ = actions::cycles::run_due_cycles(utils::unix_timestamp(), &connection);
+            }
+
+            thread::sleep(Duration::from_secs(CYCLE_CHECK_INTERVAL));
+        });
+
+        scope.spawn(|| loop {
+            {
+                let connection = utils::get_connection_or_panic();
+                let _ = actions::temporary::expire_colours(utils::unix_timestamp(), &connection);
+            }
+
+            thread::sleep(Duration::from_secs(EXPIRY_CHECK_INTERVAL));
+        });
+
         scope.spawn(|| {
             client.start()
             .expect("Could not start the client! Check network connection, make sure the discord servers are up.");
@@ -4,11 +4,17 @@ use r2d2_diesel::ConnectionManager;
 use serenity::prelude::Context;
 use DB;
 
+use actions;
+use collector;
+use colours::ParsedColour;
+use db::models::{Colour, Guild as GuildRecord};
+
 use serenity::framework::standard::{Args, CommandError};
 use serenity::model::prelude::{Guild, Message, Role};
 use serenity::prelude::RwLock;
 
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 pub fn get_connection_or_panic() -> PooledConnection<ConnectionManager<PgConnection>> {
     let manager = DB.clone();
@@ -20,10 +26,16 @@ pub fn get_connection_or_panic() -> PooledConnection<ConnectionManager<PgConnect
 
 pub fn get_or_search_role_from_arg(guild: &Guild, args: &mut Args) -> Result<Role, CommandError> {
     let role = args.single_quoted::<String>()?;
+
+    search_role_from_name(guild, &role)
+}
+
+/// Finds a role from a mention or its name.
+pub fn search_role_from_name(guild: &Guild, role: &str) -> Result<Role, CommandError> {
     role.parse::<Role>()
         .ok()
         .or_else(|| {
-            guild.role_by_name(&role).map(Role::clone)
+            guild.role_by_name(role).map(Role::clone)
             // let mut roles = guild.roles.values();
             // roles
             //     .find(|val| val.name.contains(&role))
@@ -36,6 +48,103 @@ pub fn get_or_search_role_from_arg(guild: &Guild, args: &mut Args) -> Result<Rol
         })
 }
 
+/// Reads a colour from the args, joining up function style colours like `rgb(255, 0, 0)` that span multiple args.
+pub fn get_colour_from_args(args: &mut Args) -> Result<ParsedColour<'static>, CommandError> {
+    let mut colour = args.single_quoted::<String>()?;
+
+    while colour.contains('(') && !colour.contains(')') {
+        let next = args.single::<String>().map_err(|_| {
+            CommandError(format!("The colour \"{}\" is missing a closing bracket.", colour))
+        })?;
+
+        colour.push(' ');
+        colour.push_str(&next);
+    }
+
+    colour
+        .parse::<ParsedColour>()
+        .map_err(|e| CommandError(format!("Couldn't read the colour \"{}\". {}", colour, e)))
+}
+
+/// Finds a colour by name, asking the author which one they meant when several are equally close.
+/// With `ask` off the first match is used instead. Gives back `None` when nothing matches at all.
+pub fn pick_colour_from_name(
+    msg: &Message,
+    name: &str,
+    guild: &GuildRecord,
+    connection: &PgConnection,
+    ask: bool,
+) -> Result<Option<Colour>, CommandError> {
+    let mut matches = actions::colours::find_matches_from_name(name, guild, connection);
+
+    if matches.len() < 2 || !ask {
+        return Ok(matches.into_iter().next());
+    }
+
+    let names = matches
+        .iter()
+        .map(|colour| colour.name.clone())
+        .collect::<Vec<_>>();
+    let question = format!("Several colours match \"{}\", which one did you mean?", name);
+
+    let picked = collector::ask_choice(msg, &question, &names)?;
+
+    Ok(Some(matches.swap_remove(picked)))
+}
+
+/// Seconds since the unix epoch, used for anything the bot schedules in the database.
+pub fn unix_timestamp() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Reads an interval like `90s`, `10m`, `2h` or `1d` into seconds. A plain number is read as minutes.
+pub fn parse_interval(interval: &str) -> Option<u64> {
+    let interval = interval.trim().to_lowercase();
+    let split = interval
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or_else(|| interval.len());
+    let (amount, unit) = interval.split_at(split);
+
+    let amount = amount.parse::<u64>().ok()?;
+    let multiplier = match unit.trim() {
+        "s" | "sec" | "secs" | "second" | "seconds" => 1,
+        "" | "m" | "min" | "mins" | "minute" | "minutes" => 60,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 60 * 60,
+        "d" | "day" | "days" => 24 * 60 * 60,
+        _ => return None,
+    };
+
+    amount.checked_mul(multiplier)
+}
+
+/// Writes seconds out the way people would say them, like `1h 30m`.
+pub fn format_interval(seconds: u64) -> String {
+    let units = [("d", 24 * 60 * 60), ("h", 60 * 60), ("m", 60), ("s", 1)];
+    let mut left = seconds;
+
+    let parts = units
+        .iter()
+        .filter_map(|&(unit, size)| {
+            let amount = left / size;
+            left %= size;
+
+            if amount > 0 {
+                Some(format!("{}{}", amount, unit))
+            } else {
+                None
+            }
+        }).collect::<Vec<_>>();
+
+    if parts.is_empty() {
+        "0s".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
 pub fn get_guild_result(msg: &Message) -> Result<Arc<RwLock<Guild>>, CommandError> {
     msg.guild()
         .ok_or_else(|| CommandError("Could not find guild. This command only works in a guild, if you are a in a PM / Group, please only use commands that do not require any roles".to_string()))
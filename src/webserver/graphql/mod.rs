@@ -5,7 +5,10 @@ use CONFIG;
 
 mod models;
 use self::models::{
-    common::{ColourDeleteResponse, ColourResponse, ColourUpdateInput, TokenResponse},
+    common::{
+        ColourDeleteResponse, ColourRequirementResponse, ColourResponse, ColourUpdateInput,
+        GuildSettingsInput, GuildSettingsResponse, TokenResponse,
+    },
     guild::Guild,
     me::Me,
 };
@@ -169,13 +172,8 @@ fn create_colours_fn(
                         ))
                     })
                 }).or_else(|_| {
-                    let guild = guild.clone();                    
-                    guild
-                        .create_role(|r| {
-                            r.name(&name)
-                                .colour(parsed_colour.as_role_colour().0.into())
-                        }).map_err(FieldError::from)
-                    
+                    actions::colours::create_colour_role(&guild, &name, parsed_colour)
+                        .map_err(FieldError::from)
                 })?;
 
             Ok(
@@ -297,6 +295,49 @@ graphql_object!(Mutation: Context | &self | {
         )
     }
 
+    field update_settings(&executor, guild: String, settings: GuildSettingsInput) -> FieldResult<GuildSettingsResponse> {
+        let ctx = executor.context();
+        let token = ctx.get_token()?;
+        let connection = utils::get_connection_or_panic();
+
+        let guild_id = GuildId(guild.parse::<u64>()?);
+        let cache = CACHE.read();
+        let guild = cache.guilds.get(&guild_id)
+            .ok_or_else(|| GenericError(format!("Guild ID ({}) does not exist in the bot cache!", guild_id)))?;
+        let guild = guild.read();
+
+        let requestee = Me::find_from_token(&token)?;
+
+        let valid_permissions = requestee.check_permissions(
+            &guild,
+            |permissions| permissions.administrator() || permissions.manage_guild()
+        )?;
+
+        if !valid_permissions {
+            Err(GenericError("You do not have the permissions required to perform this action.".to_string()))?
+        }
+
+        let guild_record = actions::guilds::convert_guild_to_record(guild_id, &connection)
+            .or_else(|| {
+                actions::guilds::create_new_record_from_guild(guild_id)
+                    .and_then(|record| actions::guilds::save_record_into_db(&record, &connection))
+                    .ok()
+            })
+            .ok_or_else(|| GenericError("Could not find or create a record for this guild.".to_string()))?;
+
+        let mut new_settings = guild_record.settings();
+        settings.apply_to(&mut new_settings)?;
+
+        let guild_record = actions::guilds::update_settings(&guild_record, &new_settings, &connection)?;
+
+        let self_id = cache.user.id.0;
+
+        // the settings are saved either way, a guild without colours just has no list to refresh.
+        let _ = actions::guilds::update_channel_message(&guild, self_id, &connection, false);
+
+        Ok(GuildSettingsResponse::new_from(&guild_record.settings()))
+    }
+
     field update_colour(&executor, colour_id: String, new_data: ColourUpdateInput) -> FieldResult<ColourResponse> {
         let ctx = executor.context();
         let token = ctx.get_token()?;
@@ -334,7 +375,10 @@ graphql_object!(Mutation: Context | &self | {
             Err(GenericError("You do not have the required permissions to perform this command!".to_string()))?
         }
 
-        let new_colour = new_data.hex.and_then(|h| ParsedColour::from_str(&h).ok());
+        let new_colour = match new_data.hex {
+            Some(ref hex) => Some(ParsedColour::from_str(hex)?),
+            None => None,
+        };
         let new_name = new_data.name.as_ref().map(String::as_str);
 
         let params = actions::colours::UpdateActionParams {
@@ -365,6 +409,10 @@ graphql_object!(Mutation: Context | &self | {
                 .map_err(|e| GenericError(format!("Failure during channel check due to: {:#?}", e)))?;
 
         Ok(ColourResponse {
+            aliases: actions::aliases::find_for_colour(&colour, &connection),
+            requirements: actions::requirements::find_for_colour(&colour, &connection)
+                .map(|requirement| ColourRequirementResponse::new_from(&requirement))
+                .unwrap_or_default(),
             name: colour.name,
             id: colour.id.to_string(),
             colour: format!("{}", ParsedColour::from(role.colour)),
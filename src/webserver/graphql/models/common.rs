@@ -1,11 +1,18 @@
+use base64;
+use colours::images::{ColourListImage, OutputFormat};
 use colours::models::ParsedColour;
-use db::models::Colour;
+use db::models::{Colour, ColourRequirement, GuildSettings};
+use db::settings::SettingsError;
 
 #[derive(GraphQLObject, Serialize, Deserialize, Debug, Clone)]
 pub struct ColourResponse {
     pub id: String,
     pub name: String,
     pub colour: String,
+    /// Other names the colour can be found by.
+    pub aliases: Vec<String>,
+    /// What members need before they can pick the colour.
+    pub requirements: ColourRequirementResponse,
 }
 
 impl ColourResponse {
@@ -14,6 +21,58 @@ impl ColourResponse {
             id: model.id.to_string(),
             name: model.name.clone(),
             colour: format!("{}", parsed),
+            aliases: vec![],
+            requirements: ColourRequirementResponse::default(),
+        }
+    }
+}
+
+#[derive(GraphQLObject, Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ColourRequirementResponse {
+    /// Members need at least one of these roles, empty if no role is needed.
+    pub role_ids: Vec<String>,
+    pub boosters_only: bool,
+    /// How many days a member needs to have been in the server.
+    pub min_days: i32,
+    /// Whether any of the above apply.
+    pub locked: bool,
+}
+
+impl ColourRequirementResponse {
+    pub fn new_from(requirement: &ColourRequirement) -> Self {
+        Self {
+            role_ids: requirement.role_ids.iter().map(|id| id.to_string()).collect(),
+            boosters_only: requirement.boosters_only,
+            min_days: requirement.min_days,
+            locked: !requirement.is_empty(),
+        }
+    }
+}
+
+/// A page of the rendered colour list.
+#[derive(GraphQLObject, Serialize, Deserialize, Debug, Clone)]
+pub struct ColourListImageResponse {
+    pub name: String,
+    pub mime_type: String,
+    /// The svg markup itself, or a base64 data url for the other formats.
+    pub data: String,
+}
+
+impl ColourListImageResponse {
+    pub fn new_from(image: &ColourListImage) -> Self {
+        let data = match image.format {
+            OutputFormat::Svg => String::from_utf8_lossy(&image.data).into_owned(),
+            format => format!(
+                "data:{};base64,{}",
+                format.mime_type(),
+                base64::encode(&image.data)
+            ),
+        };
+
+        Self {
+            name: image.name.clone(),
+            mime_type: image.format.mime_type().to_string(),
+            data,
         }
     }
 }
@@ -52,3 +111,113 @@ pub struct GuildInfo {
     #[serde(default)]
     pub cached: bool,
 }
+
+#[derive(GraphQLObject, Serialize, Deserialize, Debug, Clone)]
+pub struct GuildSettingsResponse {
+    pub list_style: String,
+    pub sort_method: String,
+    pub page_size: i32,
+    pub theme: String,
+    pub columns: i32,
+    pub font_family: String,
+    pub font_size: f64,
+    pub image_format: String,
+    pub show_hex_codes: bool,
+    pub contrast_warnings: bool,
+    pub reaction_roles: bool,
+    pub fuzzy_tolerance: i32,
+    pub ambiguous_match: String,
+    pub colour_codes: bool,
+    pub max_colour_distance: Option<f64>,
+    pub cleanup_delay: i32,
+    pub sweep_delay: i32,
+    /// The id of the role boosters get, `None` when it's found by name.
+    pub booster_role: Option<String>,
+    pub locale: String,
+}
+
+impl GuildSettingsResponse {
+    pub fn new_from(settings: &GuildSettings) -> Self {
+        Self {
+            list_style: settings.list_style.to_string(),
+            sort_method: settings.sort_method.to_string(),
+            page_size: settings.page_size as i32,
+            theme: settings.theme.to_string(),
+            columns: settings.columns as i32,
+            font_family: settings.font_family.clone(),
+            font_size: settings.font_size,
+            image_format: settings.image_format.to_string(),
+            show_hex_codes: settings.show_hex_codes,
+            contrast_warnings: settings.contrast_warnings,
+            reaction_roles: settings.reaction_roles,
+            fuzzy_tolerance: settings.fuzzy_tolerance as i32,
+            ambiguous_match: settings.ambiguous_match.to_string(),
+            colour_codes: settings.colour_codes,
+            max_colour_distance: settings.max_colour_distance,
+            cleanup_delay: settings.cleanup_delay as i32,
+            sweep_delay: settings.sweep_delay as i32,
+            booster_role: settings.booster_role.map(|id| id.to_string()),
+            locale: settings.locale.clone(),
+        }
+    }
+}
+
+/// Partial settings update, any field left out keeps its current value.
+#[derive(GraphQLInputObject)]
+pub struct GuildSettingsInput {
+    pub list_style: Option<String>,
+    pub sort_method: Option<String>,
+    pub page_size: Option<i32>,
+    pub theme: Option<String>,
+    pub columns: Option<i32>,
+    pub font_family: Option<String>,
+    pub font_size: Option<f64>,
+    pub image_format: Option<String>,
+    pub show_hex_codes: Option<bool>,
+    pub contrast_warnings: Option<bool>,
+    pub reaction_roles: Option<bool>,
+    pub fuzzy_tolerance: Option<i32>,
+    pub ambiguous_match: Option<String>,
+    pub colour_codes: Option<bool>,
+    pub max_colour_distance: Option<f64>,
+    pub cleanup_delay: Option<i32>,
+    pub sweep_delay: Option<i32>,
+    /// A role id, or `none` to find the role by name again.
+    pub booster_role: Option<String>,
+    pub locale: Option<String>,
+}
+
+impl GuildSettingsInput {
+    /// Applies the given fields through `GuildSettings::set` so they get the same validation as the discord command.
+    pub fn apply_to(&self, settings: &mut GuildSettings) -> Result<(), SettingsError> {
+        let changes = vec![
+            ("list_style", self.list_style.clone()),
+            ("sort_method", self.sort_method.clone()),
+            ("page_size", self.page_size.map(|v| v.to_string())),
+            ("theme", self.theme.clone()),
+            ("columns", self.columns.map(|v| v.to_string())),
+            ("font_family", self.font_family.clone()),
+            ("font_size", self.font_size.map(|v| v.to_string())),
+            ("image_format", self.image_format.clone()),
+            ("show_hex_codes", self.show_hex_codes.map(|v| v.to_string())),
+            ("contrast_warnings", self.contrast_warnings.map(|v| v.to_string())),
+            ("reaction_roles", self.reaction_roles.map(|v| v.to_string())),
+            ("fuzzy_tolerance", self.fuzzy_tolerance.map(|v| v.to_string())),
+            ("ambiguous_match", self.ambiguous_match.clone()),
+            ("colour_codes", self.colour_codes.map(|v| v.to_string())),
+            ("max_colour_distance", self.max_colour_distance.map(|v| v.to_string())),
+            ("cleanup_delay", self.cleanup_delay.map(|v| v.to_string())),
+            ("sweep_delay", self.sweep_delay.map(|v| v.to_string())),
+            ("booster_role", self.booster_role.clone()),
+            ("locale", self.locale.clone()),
+        ];
+
+        for (key, value) in changes {
+            if let Some(value) = value {
+                settings.set(key, &value)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
-use super::common::ColourResponse;
+use super::common::{
+    ColourListImageResponse, ColourRequirementResponse, ColourResponse, GuildSettingsResponse,
+};
+use colours::images::OutputFormat;
 use super::me::Me;
 use actions;
 use colours::ParsedColour;
@@ -72,6 +75,37 @@ graphql_object!(Guild: () | &self | {
     }
 
 
+    field settings() -> FieldResult<GuildSettingsResponse> {
+        let connection = utils::get_connection_or_panic();
+        let settings = actions::guilds::convert_guild_to_record(self.0.id, &connection)
+            .map(|guild| guild.settings())
+            .unwrap_or_default();
+
+        Ok(GuildSettingsResponse::new_from(&settings))
+    }
+
+    field colour_list(format: Option<String>) -> FieldResult<Vec<ColourListImageResponse>> {
+        let connection = utils::get_connection_or_panic();
+        let guild = actions::guilds::convert_guild_to_record(self.0.id, &connection)
+            .ok_or_else(|| GenericError(format!("Could not find a guild for the id {}.", self.0.id)))?;
+
+        let settings = guild.settings();
+        let format = match format {
+            Some(format) => format.parse::<OutputFormat>()?,
+            None => OutputFormat::Svg,
+        };
+
+        let colours = actions::colours::find_all(&guild, &connection)
+            .ok_or_else(|| GenericError("Error while attemptting to get the colours for this guild.".to_string()))?;
+
+        let locked = actions::requirements::find_locked_ids(&guild, &connection);
+
+        let images = actions::colours::generate_colour_images_as(&colours, &locked, &self.0, &settings, format)
+            .map_err(|e| GenericError(e.0))?;
+
+        Ok(images.iter().map(ColourListImageResponse::new_from).collect())
+    }
+
     field colours() -> FieldResult<Vec<ColourResponse>> {
         let connection = utils::get_connection_or_panic();
         let guild = actions::guilds::convert_guild_to_record(self.0.id, &connection)
@@ -85,6 +119,9 @@ graphql_object!(Guild: () | &self | {
         let colours = actions::colours::find_all(&guild, &connection)
             .ok_or_else(|| GenericError("Error while attemptting to get the colours for this guild.".to_string()))?;
 
+        let aliases = actions::aliases::find_for_guild(&guild, &connection);
+        let requirements = actions::requirements::find_for_guild(&guild, &connection);
+
         Ok(
             colours
                 .iter()
@@ -99,7 +136,17 @@ graphql_object!(Guild: () | &self | {
                             ColourResponse {
                                 id: c.id.to_string(),
                                 name: c.name.clone(),
-                                colour: format!("{}", ParsedColour::from(role.colour))
+                                colour: format!("{}", ParsedColour::from(role.colour)),
+                                aliases: aliases
+                                    .iter()
+                                    .filter(|alias| alias.colour_id == c.id)
+                                    .map(|alias| alias.name.clone())
+                                    .collect(),
+                                requirements: requirements
+                                    .iter()
+                                    .find(|requirement| requirement.colour_id == c.id)
+                                    .map(ColourRequirementResponse::new_from)
+                                    .unwrap_or_default(),
                             }
                         )
                 )